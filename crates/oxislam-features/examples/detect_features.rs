@@ -68,9 +68,10 @@ fn main() {
     println!("Loaded image: {w}x{h} from {}", args.input.display());
 
     // Convert to Gray<f32>
-    let luma = img.to_luma8();
-    let raw = luma.into_raw();
-    let gray_u8: Image<Gray<u8>> = Image::from_raw(w, h, w, raw);
+    let gray_u8 = Image::<Gray<u8>>::try_from(img.to_luma8()).unwrap_or_else(|e| {
+        eprintln!("Error: cannot convert {}: {e}", args.input.display());
+        process::exit(1);
+    });
     let gray_f32: Image<Gray<f32>> = gray_u8.view().to();
 
     // Detect keypoints
@@ -1,7 +1,8 @@
 use oxislam_geometry::Point2;
-use oxislam_image::image::{Image, ImageView};
-use oxislam_image::parallel::{par_flat_map, par_row_collect};
-use oxislam_image::{Gray, gaussian_3x3, sobel};
+use oxislam_image::filter::{SeparableKernel, StructureTensor, sobel_border_into};
+use oxislam_image::image::ImageView;
+use oxislam_image::parallel::par_flat_map;
+use oxislam_image::{Border, Gray, ImagePool};
 
 use crate::keypoint::Keypoint;
 use crate::traits::detector::KeypointDetector;
@@ -9,10 +10,9 @@ use crate::traits::detector::KeypointDetector;
 const DEFAULT_K: f32 = 0.04;
 const DEFAULT_ALPHA: f32 = 0.01;
 const DEFAULT_MIN_THRESHOLD: f32 = 1e-6;
-// Minimum image size: sobel (3x3) shrinks by 2, gaussian (3x3) shrinks by 2 more
-const MIN_IMAGE_SIZE: usize = 5;
-// Coordinate offset from response image to original: sobel (1) + gaussian (1)
-const COORD_OFFSET: f32 = 2.0;
+const BORDER: Border<Gray<f32>> = Border::Reflect101;
+/// Taps of the structure tensor's integration window, the separable form of a 3x3 Gaussian.
+const WINDOW: [f32; 3] = [0.25, 0.5, 0.25];
 
 #[derive(Debug, Clone)]
 pub struct HarrisDetector {
@@ -31,61 +31,41 @@ impl Default for HarrisDetector {
     }
 }
 
-impl HarrisDetector {
-    fn response_at(
-        &self,
-        sxx: &ImageView<Gray<f32>>,
-        syy: &ImageView<Gray<f32>>,
-        sxy: &ImageView<Gray<f32>>,
-        x: usize,
-        y: usize,
-    ) -> Gray<f32> {
-        let xx = sxx.get(x, y).value;
-        let yy = syy.get(x, y).value;
-        let xy = sxy.get(x, y).value;
-
-        let det = xx * yy - xy * xy;
-        let trace = xx + yy;
-
-        Gray::new(det - self.k * trace * trace)
-    }
-
-    fn response_map(
-        &self,
-        sxx: &ImageView<Gray<f32>>,
-        syy: &ImageView<Gray<f32>>,
-        sxy: &ImageView<Gray<f32>>,
-    ) -> Image<Gray<f32>> {
-        let w = sxx.width();
-        let h = sxx.height();
-
-        let data = par_row_collect(w, h, |x, y| self.response_at(sxx, syy, sxy, x, y));
-
-        Image::new(w, h, w, data)
-    }
-}
-
 impl KeypointDetector<Gray<f32>> for HarrisDetector {
     fn detect(&self, image: &ImageView<Gray<f32>>) -> Vec<Keypoint> {
-        if image.width() < MIN_IMAGE_SIZE || image.height() < MIN_IMAGE_SIZE {
-            return Vec::new();
-        }
+        self.detect_with_pool(image, &mut ImagePool::new())
+    }
+}
 
-        let (ix, iy) = sobel(image);
-        let ix2 = &ix * &ix;
-        let iy2 = &iy * &iy;
-        let ixiy = &ix * &iy;
-        let sxx = gaussian_3x3(&ix2.view());
-        let syy = gaussian_3x3(&iy2.view());
-        let sxy = gaussian_3x3(&ixiy.view());
+impl HarrisDetector {
+    /// Like [`KeypointDetector::detect`], but draws intermediate images from `pool` and returns
+    /// them afterwards.
+    ///
+    /// Reusing one pool across calls avoids reallocating intermediates for every frame.
+    pub fn detect_with_pool(
+        &self,
+        image: &ImageView<Gray<f32>>,
+        pool: &mut ImagePool<Gray<f32>>,
+    ) -> Vec<Keypoint> {
+        let (w, h) = (image.width(), image.height());
+        let mut ix = pool.take(w, h, Gray::new(0.0));
+        let mut iy = pool.take(w, h, Gray::new(0.0));
+
+        // Sizes match by construction, so the `_into` calls cannot fail
+        sobel_border_into(image, BORDER, &mut ix.view_mut(), &mut iy.view_mut()).unwrap();
+        let window = SeparableKernel::new(WINDOW.to_vec(), WINDOW.to_vec());
+        let tensor =
+            StructureTensor::from_gradients_pooled(&ix.view(), &iy.view(), &window, BORDER, pool);
+
+        let mut response = pool.take(w, h, Gray::new(0.0));
+        tensor.harris_into(self.k, &mut response.view_mut()).unwrap();
+        tensor.recycle(pool);
+        pool.put(ix);
+        pool.put(iy);
 
-        let response = self.response_map(&sxx.view(), &syy.view(), &sxy.view());
         let max_r = response.view().pixels().map(|p| p.value).fold(f32::NEG_INFINITY, f32::max);
         let threshold = self.min_threshold.max(self.alpha * max_r);
 
-        let w = response.width();
-        let h = response.height();
-
         let is_local_max = |x: usize, y: usize, r: f32| -> bool {
             (x == 0 || y == 0 || r > response.get(x - 1, y - 1).value)
                 && (y == 0 || r > response.get(x, y - 1).value)
@@ -102,7 +82,7 @@ impl KeypointDetector<Gray<f32>> for HarrisDetector {
                 .filter_map(|x| {
                     let r = response.get(x, y).value;
                     (r > threshold && is_local_max(x, y, r)).then(|| Keypoint {
-                        position: Point2::new(x as f32 + COORD_OFFSET, y as f32 + COORD_OFFSET),
+                        position: Point2::new(x as f32, y as f32),
                         scale: 1.0,
                         orientation: None,
                         response: r,
@@ -111,12 +91,16 @@ impl KeypointDetector<Gray<f32>> for HarrisDetector {
                 .collect()
         };
 
-        par_flat_map(0..h, extract_row)
+        let keypoints = par_flat_map(0..h, extract_row);
+        pool.put(response);
+        keypoints
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use oxislam_image::image::Image;
+
     use super::*;
 
     fn corner_image() -> Image<Gray<f32>> {
@@ -174,4 +158,30 @@ mod tests {
             assert!(found, "expected a keypoint within 1 pixel of ({ex}, {ey})");
         }
     }
+
+    #[test]
+    fn harris_reuses_pool() {
+        let img = corner_image();
+        let (w, h) = (img.width(), img.height());
+        let detector = HarrisDetector::default();
+        let mut pool = ImagePool::new();
+
+        // Drain the pool and refill it, reporting which buffers it held
+        let buffers = |pool: &mut ImagePool<Gray<f32>>| {
+            let images: Vec<_> = (0..pool.len()).map(|_| pool.take(w, h, Gray::new(0.0))).collect();
+            let mut ptrs: Vec<_> = images.iter().map(|i| i.data().as_ptr()).collect();
+            ptrs.sort();
+            images.into_iter().for_each(|i| pool.put(i));
+            ptrs
+        };
+
+        let first = detector.detect_with_pool(&img.view(), &mut pool);
+        let warm = buffers(&mut pool);
+        let second = detector.detect_with_pool(&img.view(), &mut pool);
+        assert_eq!(buffers(&mut pool), warm);
+
+        assert_eq!(first.len(), second.len());
+        assert_eq!(first[0].position, second[0].position);
+        assert_eq!(first[0].response, second[0].response);
+    }
 }
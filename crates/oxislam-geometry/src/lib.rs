@@ -2,4 +2,4 @@
 //!
 //! Re-exports commonly used types from nalgebra.
 
-pub use nalgebra::{Point2, Point3, Vector2, Vector3};
+pub use nalgebra::{Matrix2x3, Matrix3, Point2, Point3, Vector2, Vector3};
@@ -0,0 +1,93 @@
+//! Border extrapolation for operations that sample outside the image.
+
+/// How pixels outside the image bounds are extrapolated.
+///
+/// Illustrated for a row `abcdefgh`:
+///
+/// ```text
+/// Constant    vvvvvv|abcdefgh|vvvvvvv
+/// Replicate   aaaaaa|abcdefgh|hhhhhhh
+/// Reflect     fedcba|abcdefgh|hgfedcb
+/// Reflect101  gfedcb|abcdefgh|gfedcba
+/// Wrap        cdefgh|abcdefgh|abcdefg
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Border<P> {
+    /// Out-of-bounds pixels take the given value.
+    Constant(P),
+    /// Repeat the edge pixel.
+    Replicate,
+    /// Mirror including the edge pixel.
+    Reflect,
+    /// Mirror excluding the edge pixel.
+    Reflect101,
+    /// Wrap around to the opposite edge.
+    Wrap,
+}
+
+impl<P> Border<P> {
+    /// Map a possibly out-of-bounds index into `0..len`.
+    ///
+    /// Returns `None` for `Constant` when `i` is outside the range.
+    #[inline]
+    pub fn map_index(&self, i: isize, len: usize) -> Option<usize> {
+        debug_assert!(len > 0);
+        let n = len as isize;
+        if (0..n).contains(&i) {
+            return Some(i as usize);
+        }
+
+        let mapped = match self {
+            Border::Constant(_) => return None,
+            Border::Replicate => i.clamp(0, n - 1),
+            Border::Reflect => {
+                let m = i.rem_euclid(2 * n);
+                if m >= n { 2 * n - 1 - m } else { m }
+            }
+            Border::Reflect101 => {
+                if n == 1 {
+                    0
+                } else {
+                    let period = 2 * (n - 1);
+                    let m = i.rem_euclid(period);
+                    if m >= n { period - m } else { m }
+                }
+            }
+            Border::Wrap => i.rem_euclid(n),
+        };
+        Some(mapped as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map_all(border: Border<f32>, len: usize) -> Vec<Option<usize>> {
+        (-3..len as isize + 3).map(|i| border.map_index(i, len)).collect()
+    }
+
+    #[test]
+    fn border_index_mapping() {
+        let len = 4;
+        let none = [None; 3];
+
+        let constant = map_all(Border::Constant(0.0), len);
+        assert_eq!(&constant[..3], &none);
+        assert_eq!(&constant[3..7], &[Some(0), Some(1), Some(2), Some(3)]);
+        assert_eq!(&constant[7..], &none);
+
+        let expect = |v: [usize; 10]| v.map(Some).to_vec();
+        assert_eq!(map_all(Border::Replicate, len), expect([0, 0, 0, 0, 1, 2, 3, 3, 3, 3]));
+        assert_eq!(map_all(Border::Reflect, len), expect([2, 1, 0, 0, 1, 2, 3, 3, 2, 1]));
+        assert_eq!(map_all(Border::Reflect101, len), expect([3, 2, 1, 0, 1, 2, 3, 2, 1, 0]));
+        assert_eq!(map_all(Border::Wrap, len), expect([1, 2, 3, 0, 1, 2, 3, 0, 1, 2]));
+    }
+
+    #[test]
+    fn reflect101_single_pixel() {
+        let border = Border::<f32>::Reflect101;
+        assert_eq!(border.map_index(-2, 1), Some(0));
+        assert_eq!(border.map_index(5, 1), Some(0));
+    }
+}
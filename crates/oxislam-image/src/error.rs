@@ -0,0 +1,65 @@
+use std::fmt;
+
+/// Errors from constructing or combining images with invalid dimensions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImageError {
+    /// Width or height is zero.
+    ZeroSize { width: usize, height: usize },
+    /// Row stride is smaller than the image width.
+    StrideTooSmall { stride: usize, width: usize },
+    /// Buffer holds fewer elements than `stride * height`.
+    BufferTooShort { len: usize, required: usize },
+    /// Raw buffer length is not a whole number of pixels.
+    PartialPixel { len: usize, channels: usize },
+    /// Byte buffer is not aligned for the pixel type.
+    Misaligned { align: usize },
+    /// Byte stride is not a multiple of the pixel size.
+    StrideNotMultiple { stride_bytes: usize, pixel_size: usize },
+    /// Two images that must have the same size do not.
+    SizeMismatch { expected: (usize, usize), actual: (usize, usize) },
+    /// Image is smaller than an operation requires.
+    TooSmall { width: usize, height: usize, min_width: usize, min_height: usize },
+    /// Buffer size for the given dimensions overflows `usize`.
+    TooLarge { width: usize, height: usize },
+}
+
+impl fmt::Display for ImageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImageError::ZeroSize { width, height } => {
+                write!(f, "image size must be non-zero, got {width}x{height}")
+            }
+            ImageError::StrideTooSmall { stride, width } => {
+                write!(f, "stride {stride} is smaller than width {width}")
+            }
+            ImageError::BufferTooShort { len, required } => {
+                write!(f, "buffer holds {len} elements, but {required} are required")
+            }
+            ImageError::PartialPixel { len, channels } => {
+                write!(f, "buffer of {len} values is not a multiple of {channels} channels")
+            }
+            ImageError::Misaligned { align } => {
+                write!(f, "buffer is not aligned to {align} bytes")
+            }
+            ImageError::StrideNotMultiple { stride_bytes, pixel_size } => {
+                write!(
+                    f,
+                    "stride of {stride_bytes} bytes is not a multiple of pixel size {pixel_size}"
+                )
+            }
+            ImageError::SizeMismatch { expected, actual } => write!(
+                f,
+                "image size mismatch: expected {}x{}, got {}x{}",
+                expected.0, expected.1, actual.0, actual.1
+            ),
+            ImageError::TooSmall { width, height, min_width, min_height } => {
+                write!(f, "image must be at least {min_width}x{min_height}, got {width}x{height}")
+            }
+            ImageError::TooLarge { width, height } => {
+                write!(f, "buffer size of a {width}x{height} image overflows usize")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ImageError {}
@@ -0,0 +1,75 @@
+use crate::border::Border;
+use crate::image::{Image, ImageView};
+use crate::parallel::par_row_collect;
+use crate::pixel::{Channel, Gray};
+
+/// Edge-preserving bilateral filter.
+///
+/// Each pixel becomes a weighted mean of its neighbors within a radius of `ceil(3 *
+/// sigma_space)`, where the weight falls off with both the spatial distance (Gaussian of
+/// `sigma_space` pixels) and the difference in value (Gaussian of `sigma_range`, in channel
+/// units). Neighbors across a step much larger than `sigma_range` barely contribute, so edges
+/// stay sharp while flat regions are smoothed.
+///
+/// # Panics
+///
+/// Panics if either sigma is not positive.
+pub fn bilateral<T: Channel>(
+    image: &ImageView<Gray<T>>,
+    sigma_space: f32,
+    sigma_range: f32,
+    border: Border<Gray<T>>,
+) -> Image<Gray<T>> {
+    assert!(sigma_space > 0.0 && sigma_range > 0.0, "Sigmas must be positive");
+
+    let r = (3.0 * sigma_space).ceil() as isize;
+    let space_denom = 2.0 * sigma_space * sigma_space;
+    let range_denom = 2.0 * sigma_range * sigma_range;
+    let spatial: Vec<(isize, isize, f32)> = (-r..=r)
+        .flat_map(|dy| (-r..=r).map(move |dx| (dx, dy)))
+        .filter(|&(dx, dy)| dx * dx + dy * dy <= r * r)
+        .map(|(dx, dy)| (dx, dy, (-((dx * dx + dy * dy) as f32) / space_denom).exp()))
+        .collect();
+
+    let (w, h) = (image.width(), image.height());
+    let data = par_row_collect(w, h, |x, y| {
+        let center = image.get(x, y).value.to_f32();
+        let (mut sum, mut weights) = (0.0, 0.0);
+        for &(dx, dy, ws) in &spatial {
+            let v = image.get_border(x as isize + dx, y as isize + dy, border).value.to_f32();
+            let d = v - center;
+            let weight = ws * (-d * d / range_denom).exp();
+            sum += weight * v;
+            weights += weight;
+        }
+        // The center pixel always has weight 1, so `weights` is positive
+        Gray::new(T::from_f32(sum / weights))
+    });
+    Image::new(w, h, w, data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bilateral_smooths_noise_but_keeps_edges() {
+        // Left half around 10, right half around 100, with small alternating noise
+        let data: Vec<Gray<f32>> = (0..12 * 8)
+            .map(|i| {
+                let (x, y) = (i % 12, i / 12);
+                let noise = if (x + y) % 2 == 0 { 1.0 } else { -1.0 };
+                Gray::new(if x < 6 { 10.0 } else { 100.0 } + noise)
+            })
+            .collect();
+        let img = Image::new(12, 8, 12, data);
+        let out = bilateral(&img.view(), 1.5, 5.0, Border::Reflect101);
+
+        for y in 1..7 {
+            assert!((out.get(2, y).value - 10.0).abs() < 0.5);
+            assert!((out.get(5, y).value - 10.0).abs() < 1.0);
+            assert!((out.get(6, y).value - 100.0).abs() < 1.0);
+            assert!((out.get(9, y).value - 100.0).abs() < 0.5);
+        }
+    }
+}
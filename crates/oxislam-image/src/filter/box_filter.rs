@@ -0,0 +1,175 @@
+use crate::error::ImageError;
+use crate::image::{Image, ImageView, ImageViewMut, check_size, fill_with};
+use crate::integral::{IntegralImage, IntegralPixel};
+use crate::parallel::par_row_collect;
+use crate::pixel::Gray;
+
+/// Window `[x - radius, x + radius]` clipped to `0..len`, as `(start, len)`.
+#[inline]
+fn window(x: usize, radius: usize, len: usize) -> (usize, usize) {
+    let start = x.saturating_sub(radius);
+    let end = (x + radius + 1).min(len);
+    (start, end - start)
+}
+
+#[inline]
+fn window_mean(integral: &IntegralImage, radius: usize, x: usize, y: usize) -> Gray<f32> {
+    let (x0, ww) = window(x, radius, integral.width());
+    let (y0, wh) = window(y, radius, integral.height());
+    Gray::new((integral.rect_sum(x0, y0, ww, wh) / (ww * wh) as f64) as f32)
+}
+
+/// Mean over the `(2 * radius + 1)`-sized square window around each pixel.
+///
+/// Runs in constant time per pixel regardless of `radius`. Windows are clipped at the image
+/// border and averaged over the pixels they actually cover.
+pub fn box_filter<P: IntegralPixel>(image: &ImageView<P>, radius: usize) -> Image<Gray<f32>> {
+    let integral = IntegralImage::new(image);
+    box_mean(&integral, radius)
+}
+
+/// Box mean computed from an existing integral image.
+pub fn box_mean(integral: &IntegralImage, radius: usize) -> Image<Gray<f32>> {
+    let w = integral.width();
+    let h = integral.height();
+
+    let data = par_row_collect(w, h, |x, y| window_mean(integral, radius, x, y));
+    Image::new(w, h, w, data)
+}
+
+/// Like [`box_filter`], but writes into `dst`, which must have the same size as the input.
+pub fn box_filter_into<P: IntegralPixel>(
+    image: &ImageView<P>,
+    radius: usize,
+    dst: &mut ImageViewMut<Gray<f32>>,
+) -> Result<(), ImageError> {
+    check_size(dst, image.width(), image.height())?;
+    box_mean_into(&IntegralImage::new(image), radius, dst)
+}
+
+/// Like [`box_mean`], but writes into `dst`, which must have the size of the source image.
+pub fn box_mean_into(
+    integral: &IntegralImage,
+    radius: usize,
+    dst: &mut ImageViewMut<Gray<f32>>,
+) -> Result<(), ImageError> {
+    let w = integral.width();
+    let h = integral.height();
+    check_size(dst, w, h)?;
+
+    fill_with(dst, |x, y| window_mean(integral, radius, x, y));
+    Ok(())
+}
+
+/// Local mean and variance over the `(2 * radius + 1)`-sized square window around each pixel.
+///
+/// Windows are clipped at the image border like in [`box_filter`].
+pub fn local_mean_variance<P: IntegralPixel>(
+    image: &ImageView<P>,
+    radius: usize,
+) -> (Image<Gray<f32>>, Image<Gray<f32>>) {
+    let integral = IntegralImage::with_squares(image);
+    let w = integral.width();
+    let h = integral.height();
+
+    let stats = par_row_collect(w, h, |x, y| window_mean_variance(&integral, radius, x, y));
+
+    let mean = stats.iter().map(|&(m, _)| Gray::new(m)).collect();
+    let variance = stats.iter().map(|&(_, v)| Gray::new(v)).collect();
+    (Image::new(w, h, w, mean), Image::new(w, h, w, variance))
+}
+
+/// Like [`local_mean_variance`], but writes into `mean` and `variance`, which must have the same
+/// size as the input.
+pub fn local_mean_variance_into<P: IntegralPixel>(
+    image: &ImageView<P>,
+    radius: usize,
+    mean: &mut ImageViewMut<Gray<f32>>,
+    variance: &mut ImageViewMut<Gray<f32>>,
+) -> Result<(), ImageError> {
+    check_size(mean, image.width(), image.height())?;
+    check_size(variance, image.width(), image.height())?;
+
+    // Both outputs come from the same window sums, so fill them row by row together
+    let integral = IntegralImage::with_squares(image);
+    for (y, (mean_row, variance_row)) in mean.rows_mut().zip(variance.rows_mut()).enumerate() {
+        for (x, (m, v)) in mean_row.iter_mut().zip(variance_row).enumerate() {
+            let (wm, wv) = window_mean_variance(&integral, radius, x, y);
+            (*m, *v) = (Gray::new(wm), Gray::new(wv));
+        }
+    }
+    Ok(())
+}
+
+/// Mean and variance of a window, computed in `f64` and rounded only for the result.
+#[inline]
+fn window_mean_variance(integral: &IntegralImage, radius: usize, x: usize, y: usize) -> (f32, f32) {
+    let (x0, ww) = window(x, radius, integral.width());
+    let (y0, wh) = window(y, radius, integral.height());
+    let n = (ww * wh) as f64;
+    let mean = integral.rect_sum(x0, y0, ww, wh) / n;
+    let sq_mean = integral.rect_sq_sum(x0, y0, ww, wh).unwrap() / n;
+    (mean as f32, (sq_mean - mean * mean).max(0.0) as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn box_filter_clips_at_border() {
+        // [0, 3, 6]
+        // [9, 12, 15]
+        let data: Vec<Gray<u8>> = (0..6).map(|v| Gray::new(v * 3)).collect();
+        let img = Image::new(3, 2, 3, data);
+        let out = box_filter(&img.view(), 1);
+
+        assert_eq!(out.get(0, 0).value, 6.0); // mean of 0, 3, 9, 12
+        assert_eq!(out.get(1, 0).value, 7.5); // mean of the whole image
+        assert_eq!(out.get(2, 1).value, 9.0); // mean of 3, 6, 12, 15
+    }
+
+    #[test]
+    fn local_variance() {
+        // Checkerboard of 0 and 1
+        let data: Vec<Gray<f32>> = (0..25).map(|v| Gray::new((v % 2) as f32)).collect();
+        let img = Image::new(5, 5, 5, data);
+        let (mean, variance) = local_mean_variance(&img.view(), 1);
+
+        // 3x3 window centered on a 0 holds four 1s
+        assert!((mean.get(2, 2).value - 4.0 / 9.0).abs() < 1e-6);
+        assert!((variance.get(2, 2).value - 20.0 / 81.0).abs() < 1e-6);
+
+        // Near-flat high values, where rounding the mean before squaring would swamp the result
+        let data = [40000u16, 40000, 40001].map(Gray::new).to_vec();
+        let img = Image::new(3, 1, 3, data);
+        let mut mean = Image::filled(3, 1, Gray::new(0.0));
+        let mut var = Image::filled(3, 1, Gray::new(0.0));
+        local_mean_variance_into(&img.view(), 1, &mut mean.view_mut(), &mut var.view_mut())
+            .unwrap();
+        assert!((var.get(1, 0).value - 2.0 / 9.0).abs() < 1e-3);
+        assert!((var.get(0, 0).value - 0.0).abs() < 1e-3);
+        assert!((var.get(2, 0).value - 0.25).abs() < 1e-3);
+        assert_eq!(var.data(), local_mean_variance(&img.view(), 1).1.data());
+
+        let flat = Image::filled(4, 4, Gray::new(0.3f32));
+        let (_, variance) = local_mean_variance(&flat.view(), 2);
+        assert!(variance.view().pixels().all(|p| p.value.abs() < 1e-6));
+    }
+
+    #[test]
+    fn into_variants_match() {
+        let data: Vec<Gray<u8>> = (0..42).map(|v| Gray::new((v * 37 % 23) as u8)).collect();
+        let img = Image::new(7, 6, 7, data);
+
+        let mut out = Image::filled(7, 6, Gray::new(0.0));
+        box_filter_into(&img.view(), 2, &mut out.view_mut()).unwrap();
+        assert_eq!(out.data(), box_filter(&img.view(), 2).data());
+
+        let mut var = Image::filled(7, 6, Gray::new(0.0));
+        local_mean_variance_into(&img.view(), 1, &mut out.view_mut(), &mut var.view_mut()).unwrap();
+        let (mean, variance) = local_mean_variance(&img.view(), 1);
+        assert_eq!(out.data(), mean.data());
+        assert_eq!(var.data(), variance.data());
+    }
+}
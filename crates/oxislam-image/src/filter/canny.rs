@@ -0,0 +1,207 @@
+use super::gaussian::gaussian;
+use super::gradient::gradient_magnitude;
+use super::sobel::sobel_border;
+use crate::border::Border;
+use crate::image::{Image, ImageView};
+use crate::parallel::par_row_collect;
+use crate::pixel::Gray;
+
+const BORDER: Border<Gray<f32>> = Border::Reflect101;
+/// Value of edge pixels in the output map.
+pub const EDGE: u8 = 255;
+
+const TAN_22_5: f32 = 0.414_213_56;
+const TAN_67_5: f32 = 2.414_213_6;
+
+/// An edge pixel found by [`canny_edges`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EdgePixel {
+    pub x: usize,
+    pub y: usize,
+    /// Sobel gradient magnitude.
+    pub magnitude: f32,
+    /// Gradient direction `atan2(iy, ix)` in radians, normal to the edge.
+    pub direction: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Candidate {
+    None,
+    Weak,
+    Strong,
+}
+
+struct EdgeMap {
+    edges: Image<Gray<u8>>,
+    ix: Image<Gray<f32>>,
+    iy: Image<Gray<f32>>,
+    magnitude: Image<Gray<f32>>,
+}
+
+/// Whether the magnitude at `(x, y)` is a maximum along the gradient direction, quantized to
+/// one of four axes. Ties are broken towards the left/top neighbor so plateaus stay one pixel
+/// thick.
+fn is_ridge(magnitude: &ImageView<Gray<f32>>, gx: f32, gy: f32, x: usize, y: usize) -> bool {
+    let (ax, ay) = (gx.abs(), gy.abs());
+    let (dx, dy) = if ay <= ax * TAN_22_5 {
+        (1, 0)
+    } else if ay >= ax * TAN_67_5 {
+        (0, 1)
+    } else if (gx > 0.0) == (gy > 0.0) {
+        (1, 1)
+    } else {
+        (-1, 1)
+    };
+
+    let at = |sx: isize, sy: isize| -> f32 {
+        let (w, h) = (magnitude.width() as isize, magnitude.height() as isize);
+        if (0..w).contains(&sx) && (0..h).contains(&sy) {
+            magnitude.get(sx as usize, sy as usize).value
+        } else {
+            0.0
+        }
+    };
+    let (x, y) = (x as isize, y as isize);
+    let m = at(x, y);
+    m > at(x - dx, y - dy) && m >= at(x + dx, y + dy)
+}
+
+fn edge_map(image: &ImageView<Gray<f32>>, sigma: f32, low: f32, high: f32) -> EdgeMap {
+    assert!(0.0 < low && low <= high, "Thresholds must satisfy 0 < low <= high");
+
+    let (ix, iy) = if sigma > 0.0 {
+        sobel_border(&gaussian(image, sigma, BORDER).view(), BORDER)
+    } else {
+        sobel_border(image, BORDER)
+    };
+    let magnitude = gradient_magnitude(&ix.view(), &iy.view());
+
+    let (w, h) = (image.width(), image.height());
+    let candidates = par_row_collect(w, h, |x, y| {
+        let m = magnitude.get(x, y).value;
+        let (gx, gy) = (ix.get(x, y).value, iy.get(x, y).value);
+        if m < low || !is_ridge(&magnitude.view(), gx, gy, x, y) {
+            Candidate::None
+        } else if m >= high {
+            Candidate::Strong
+        } else {
+            Candidate::Weak
+        }
+    });
+
+    // Hysteresis: grow edges from strong pixels through 8-connected weak ones
+    let mut edges = vec![Gray::new(0u8); w * h];
+    let mut stack: Vec<usize> =
+        (0..w * h).filter(|&i| candidates[i] == Candidate::Strong).collect();
+    for &i in &stack {
+        edges[i] = Gray::new(EDGE);
+    }
+    while let Some(i) = stack.pop() {
+        let (x, y) = (i % w, i / w);
+        for ny in y.saturating_sub(1)..(y + 2).min(h) {
+            for nx in x.saturating_sub(1)..(x + 2).min(w) {
+                let n = ny * w + nx;
+                if candidates[n] == Candidate::Weak && edges[n].value == 0 {
+                    edges[n] = Gray::new(EDGE);
+                    stack.push(n);
+                }
+            }
+        }
+    }
+
+    EdgeMap { edges: Image::new(w, h, w, edges), ix, iy, magnitude }
+}
+
+/// Canny edge detector, returning a binary map with [`EDGE`] on edge pixels and 0 elsewhere.
+///
+/// The image is blurred with a Gaussian of `sigma` (skipped if zero) before taking Sobel
+/// gradients. Pixels that are local maxima of the gradient magnitude along the gradient
+/// direction become edges if their magnitude reaches `high`, or if it reaches `low` and they
+/// connect to such a pixel through other edge pixels.
+///
+/// # Panics
+///
+/// Panics unless `0 < low <= high`.
+pub fn canny(image: &ImageView<Gray<f32>>, sigma: f32, low: f32, high: f32) -> Image<Gray<u8>> {
+    edge_map(image, sigma, low, high).edges
+}
+
+/// Like [`canny`], but also returns the edge pixels in row-major order, with their gradient.
+pub fn canny_edges(
+    image: &ImageView<Gray<f32>>,
+    sigma: f32,
+    low: f32,
+    high: f32,
+) -> (Image<Gray<u8>>, Vec<EdgePixel>) {
+    let EdgeMap { edges, ix, iy, magnitude } = edge_map(image, sigma, low, high);
+    let (w, h) = (edges.width(), edges.height());
+    let pixels = (0..h)
+        .flat_map(|y| (0..w).map(move |x| (x, y)))
+        .filter(|&(x, y)| edges.get(x, y).value == EDGE)
+        .map(|(x, y)| EdgePixel {
+            x,
+            y,
+            magnitude: magnitude.get(x, y).value,
+            direction: iy.get(x, y).value.atan2(ix.get(x, y).value),
+        })
+        .collect();
+    (edges, pixels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square() -> Image<Gray<f32>> {
+        // 20x20 image with a bright 10x10 square at (5, 5)
+        let data = (0..400)
+            .map(|i| {
+                let (x, y) = (i % 20, i / 20);
+                Gray::new(if (5..15).contains(&x) && (5..15).contains(&y) { 1.0 } else { 0.0 })
+            })
+            .collect();
+        Image::new(20, 20, 20, data)
+    }
+
+    #[test]
+    fn canny_traces_thin_outline() {
+        let (edges, pixels) = canny_edges(&square().view(), 1.0, 0.1, 0.3);
+
+        // Every row crossing the square has exactly one edge on each side
+        for y in 7..13 {
+            let row: Vec<usize> = (0..20).filter(|&x| edges.get(x, y).value == EDGE).collect();
+            assert_eq!(row.len(), 2, "row {y}: {row:?}");
+            assert!((4..=5).contains(&row[0]) && (14..=15).contains(&row[1]));
+        }
+        assert_eq!(edges.get(0, 0).value, 0);
+        assert_eq!(edges.get(10, 10).value, 0);
+
+        assert_eq!(pixels.len(), edges.view().pixels().filter(|p| p.value == EDGE).count());
+        // The gradient on the left side points right, into the bright square
+        let left = pixels.iter().find(|p| p.y == 10 && p.x < 10).unwrap();
+        assert!(left.direction.abs() < 0.1);
+        assert!(left.magnitude > 0.3);
+    }
+
+    #[test]
+    fn hysteresis_drops_isolated_weak_edges() {
+        // A strong step on the left and a faint one on the right
+        let data = (0..16 * 8)
+            .map(|i| {
+                Gray::new(if i % 16 < 4 {
+                    0.0
+                } else if i % 16 < 12 {
+                    1.0
+                } else {
+                    1.2
+                })
+            })
+            .collect();
+        let img = Image::new(16, 8, 16, data);
+
+        let weak_only = |edges: &Image<Gray<u8>>| (8..16).any(|x| edges.get(x, 4).value == EDGE);
+        assert!(weak_only(&canny(&img.view(), 0.0, 0.5, 0.5)));
+        assert!(!weak_only(&canny(&img.view(), 0.0, 0.5, 2.0)));
+        assert!(!weak_only(&canny(&img.view(), 0.0, 1.0, 2.0)));
+    }
+}
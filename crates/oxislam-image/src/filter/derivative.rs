@@ -0,0 +1,210 @@
+use super::separable::{SeparableKernel, apply_separable, gaussian_kernel_1d};
+use crate::border::Border;
+use crate::image::{Image, ImageView, map2};
+use crate::pixel::Gray;
+
+/// Correlate `a` with the 2-tap kernel `b`, growing it by one tap.
+fn extend(a: &[f32], b: [f32; 2]) -> Vec<f32> {
+    let mut out = vec![0.0; a.len() + 1];
+    for (i, &v) in a.iter().enumerate() {
+        out[i] += v * b[0];
+        out[i + 1] += v * b[1];
+    }
+    out
+}
+
+/// Unnormalized 1D Sobel kernel of odd length `aperture` for the derivative of the given
+/// `order`: a binomial smoothing kernel differentiated `order` times.
+///
+/// # Panics
+///
+/// Panics if `aperture` is even or not larger than `order`.
+pub fn sobel_kernel_1d(aperture: usize, order: usize) -> Vec<f32> {
+    assert!(aperture % 2 == 1, "Aperture must be odd");
+    assert!(order < aperture, "Derivative order must be smaller than the aperture");
+
+    let smooth = (1..aperture - order).fold(vec![1.0], |k, _| extend(&k, [1.0, 1.0]));
+    (0..order).fold(smooth, |k, _| extend(&k, [-1.0, 1.0]))
+}
+
+/// Separable Sobel kernel for the `dx`-th derivative in x and the `dy`-th in y.
+///
+/// `sobel_kernel(3, 1, 0)` is the separable form of [`SOBEL_X`](super::sobel::SOBEL_X).
+pub fn sobel_kernel(aperture: usize, dx: usize, dy: usize) -> SeparableKernel {
+    SeparableKernel::new(sobel_kernel_1d(aperture, dx), sobel_kernel_1d(aperture, dy))
+}
+
+/// Separable 3x3 Scharr kernel for the first derivative along x (`dx == 1`) or y (`dy == 1`).
+///
+/// Scharr weights are more rotationally symmetric than Sobel's, giving more accurate gradient
+/// directions.
+///
+/// # Panics
+///
+/// Panics unless exactly one of `dx` and `dy` is 1 and the other is 0.
+pub fn scharr_kernel(dx: usize, dy: usize) -> SeparableKernel {
+    let (diff, smooth) = (vec![-1.0, 0.0, 1.0], vec![3.0, 10.0, 3.0]);
+    match (dx, dy) {
+        (1, 0) => SeparableKernel::new(diff, smooth),
+        (0, 1) => SeparableKernel::new(smooth, diff),
+        _ => panic!("Scharr kernels only cover first derivatives along one axis"),
+    }
+}
+
+/// Sobel gradients with an arbitrary odd `aperture`, with the same size as the input.
+///
+/// Larger apertures smooth more along the other axis, trading localization for noise
+/// robustness.
+pub fn sobel_aperture(
+    image: &ImageView<Gray<f32>>,
+    aperture: usize,
+    border: Border<Gray<f32>>,
+) -> (Image<Gray<f32>>, Image<Gray<f32>>) {
+    let ix = apply_separable(image, &sobel_kernel(aperture, 1, 0), border);
+    let iy = apply_separable(image, &sobel_kernel(aperture, 0, 1), border);
+    (ix, iy)
+}
+
+/// Scharr gradients, with the same size as the input.
+pub fn scharr(
+    image: &ImageView<Gray<f32>>,
+    border: Border<Gray<f32>>,
+) -> (Image<Gray<f32>>, Image<Gray<f32>>) {
+    let ix = apply_separable(image, &scharr_kernel(1, 0), border);
+    let iy = apply_separable(image, &scharr_kernel(0, 1), border);
+    (ix, iy)
+}
+
+/// Per-pixel second derivatives of an image, the entries of its Hessian matrix.
+#[derive(Debug)]
+pub struct Hessian {
+    pub ixx: Image<Gray<f32>>,
+    pub iyy: Image<Gray<f32>>,
+    pub ixy: Image<Gray<f32>>,
+}
+
+/// Second derivatives from Sobel kernels of the given `aperture`, with the same size as the
+/// input.
+pub fn second_derivatives(
+    image: &ImageView<Gray<f32>>,
+    aperture: usize,
+    border: Border<Gray<f32>>,
+) -> Hessian {
+    Hessian {
+        ixx: apply_separable(image, &sobel_kernel(aperture, 2, 0), border),
+        iyy: apply_separable(image, &sobel_kernel(aperture, 0, 2), border),
+        ixy: apply_separable(image, &sobel_kernel(aperture, 1, 1), border),
+    }
+}
+
+/// Sum of the two separable second-derivative passes `kxx` and `kyy`.
+fn sum_of(
+    image: &ImageView<Gray<f32>>,
+    kxx: &SeparableKernel,
+    kyy: &SeparableKernel,
+    border: Border<Gray<f32>>,
+) -> Image<Gray<f32>> {
+    let ixx = apply_separable(image, kxx, border);
+    let iyy = apply_separable(image, kyy, border);
+    map2(&ixx.view(), &iyy.view(), |a, b| Gray::new(a.value + b.value))
+}
+
+/// Discrete Laplacian `Ixx + Iyy` with the 4-neighbor stencil, as two 1D passes.
+pub fn laplacian(image: &ImageView<Gray<f32>>, border: Border<Gray<f32>>) -> Image<Gray<f32>> {
+    let (d2, id) = (vec![1.0, -2.0, 1.0], vec![1.0]);
+    let kxx = SeparableKernel::new(d2.clone(), id.clone());
+    let kyy = SeparableKernel::new(id, d2);
+    sum_of(image, &kxx, &kyy, border)
+}
+
+/// Second derivative of a Gaussian with standard deviation `sigma`, sampled on the same support
+/// as [`gaussian_kernel_1d`] and corrected so that it ignores constants and maps `x²` to 2.
+fn gaussian_second_derivative_1d(sigma: f32) -> Vec<f32> {
+    let g = gaussian_kernel_1d(sigma);
+    let r = (g.len() / 2) as f32;
+    let s2 = sigma * sigma;
+    let mut k: Vec<f32> = g
+        .iter()
+        .enumerate()
+        .map(|(i, &v)| {
+            let x = i as f32 - r;
+            (x * x / s2 - 1.0) / s2 * v
+        })
+        .collect();
+
+    let mean = k.iter().sum::<f32>() / k.len() as f32;
+    k.iter_mut().for_each(|v| *v -= mean);
+    let moment: f32 = k.iter().enumerate().map(|(i, v)| v * (i as f32 - r).powi(2)).sum();
+    k.iter_mut().for_each(|v| *v *= 2.0 / moment);
+    k
+}
+
+/// Laplacian of Gaussian with standard deviation `sigma`, as two separable passes.
+///
+/// Responds with `Ixx + Iyy` of the smoothed image; multiply by `sigma²` for a scale-normalized
+/// response.
+pub fn laplacian_of_gaussian(
+    image: &ImageView<Gray<f32>>,
+    sigma: f32,
+    border: Border<Gray<f32>>,
+) -> Image<Gray<f32>> {
+    let (d2, g) = (gaussian_second_derivative_1d(sigma), gaussian_kernel_1d(sigma));
+    let kxx = SeparableKernel::new(d2.clone(), g.clone());
+    let kyy = SeparableKernel::new(g, d2);
+    sum_of(image, &kxx, &kyy, border)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filter::sobel::sobel_border;
+
+    fn quadratic() -> Image<Gray<f32>> {
+        // f(x, y) = x² + 3y² + 2xy + x
+        let data = (0..20 * 20)
+            .map(|i| {
+                let (x, y) = ((i % 20) as f32, (i / 20) as f32);
+                Gray::new(x * x + 3.0 * y * y + 2.0 * x * y + x)
+            })
+            .collect();
+        Image::new(20, 20, 20, data)
+    }
+
+    #[test]
+    fn sobel_kernels() {
+        assert_eq!(sobel_kernel_1d(3, 1), [-1.0, 0.0, 1.0]);
+        assert_eq!(sobel_kernel_1d(5, 0), [1.0, 4.0, 6.0, 4.0, 1.0]);
+        assert_eq!(sobel_kernel_1d(5, 1), [-1.0, -2.0, 0.0, 2.0, 1.0]);
+        assert_eq!(sobel_kernel_1d(5, 2), [1.0, 0.0, -2.0, 0.0, 1.0]);
+        assert_eq!(sobel_kernel_1d(7, 1), [-1.0, -4.0, -5.0, 0.0, 5.0, 4.0, 1.0]);
+
+        let img = quadratic();
+        let (ix3, iy3) = sobel_aperture(&img.view(), 3, Border::Replicate);
+        let (ix, iy) = sobel_border(&img.view(), Border::Replicate);
+        assert_eq!(ix3.data(), ix.data());
+        assert_eq!(iy3.data(), iy.data());
+    }
+
+    #[test]
+    fn derivatives_of_quadratic() {
+        let img = quadratic();
+        let (x, y) = (10, 8);
+        // df/dx = 2x + 2y + 1, df/dy = 6y + 2x; Sobel gains are 8, 128 and 2048 for apertures
+        // 3, 5 and 7, and Scharr's is 32
+        for (aperture, gain) in [(3, 8.0), (5, 128.0), (7, 2048.0)] {
+            let (ix, iy) = sobel_aperture(&img.view(), aperture, Border::Replicate);
+            assert_eq!(ix.get(x, y).value, gain * 37.0);
+            assert_eq!(iy.get(x, y).value, gain * 68.0);
+        }
+        let (ix, _) = scharr(&img.view(), Border::Replicate);
+        assert_eq!(ix.get(x, y).value, 32.0 * 37.0);
+
+        let h = second_derivatives(&img.view(), 3, Border::Replicate);
+        assert_eq!((h.ixx.get(x, y).value, h.iyy.get(x, y).value), (2.0 * 4.0, 6.0 * 4.0));
+        assert_eq!(h.ixy.get(x, y).value, 2.0 * 4.0);
+
+        assert_eq!(laplacian(&img.view(), Border::Replicate).get(x, y).value, 8.0);
+        let log = laplacian_of_gaussian(&img.view(), 1.2, Border::Replicate);
+        assert!((log.get(x, y).value - 8.0).abs() < 1e-2);
+    }
+}
@@ -1,5 +1,10 @@
-use super::kernel::{Kernel, apply_kernel};
-use crate::image::{Image, ImageView};
+use super::kernel::{
+    Kernel, apply_kernel, apply_kernel_border, apply_kernel_border_into, apply_kernel_into,
+};
+use super::separable::{SeparableKernel, apply_separable, apply_separable_into};
+use crate::border::Border;
+use crate::error::ImageError;
+use crate::image::{Image, ImageView, ImageViewMut};
 use crate::pixel::Gray;
 
 #[rustfmt::skip]
@@ -25,3 +30,71 @@ pub fn gaussian_3x3(image: &ImageView<Gray<f32>>) -> Image<Gray<f32>> {
 pub fn gaussian_5x5(image: &ImageView<Gray<f32>>) -> Image<Gray<f32>> {
     apply_kernel(image, &GAUSSIAN_5X5)
 }
+
+pub fn gaussian_3x3_border(
+    image: &ImageView<Gray<f32>>,
+    border: Border<Gray<f32>>,
+) -> Image<Gray<f32>> {
+    apply_kernel_border(image, &GAUSSIAN_3X3, border)
+}
+
+pub fn gaussian_5x5_border(
+    image: &ImageView<Gray<f32>>,
+    border: Border<Gray<f32>>,
+) -> Image<Gray<f32>> {
+    apply_kernel_border(image, &GAUSSIAN_5X5, border)
+}
+
+/// Gaussian blur with arbitrary `sigma`, applied as two 1D passes.
+pub fn gaussian(
+    image: &ImageView<Gray<f32>>,
+    sigma: f32,
+    border: Border<Gray<f32>>,
+) -> Image<Gray<f32>> {
+    apply_separable(image, &SeparableKernel::gaussian(sigma), border)
+}
+
+/// Like [`gaussian_3x3`], but writes into `dst`; see [`apply_kernel_into`].
+pub fn gaussian_3x3_into(
+    image: &ImageView<Gray<f32>>,
+    dst: &mut ImageViewMut<Gray<f32>>,
+) -> Result<(), ImageError> {
+    apply_kernel_into(image, &GAUSSIAN_3X3, dst)
+}
+
+/// Like [`gaussian_5x5`], but writes into `dst`; see [`apply_kernel_into`].
+pub fn gaussian_5x5_into(
+    image: &ImageView<Gray<f32>>,
+    dst: &mut ImageViewMut<Gray<f32>>,
+) -> Result<(), ImageError> {
+    apply_kernel_into(image, &GAUSSIAN_5X5, dst)
+}
+
+/// Like [`gaussian_3x3_border`], but writes into `dst`; see [`apply_kernel_border_into`].
+pub fn gaussian_3x3_border_into(
+    image: &ImageView<Gray<f32>>,
+    border: Border<Gray<f32>>,
+    dst: &mut ImageViewMut<Gray<f32>>,
+) -> Result<(), ImageError> {
+    apply_kernel_border_into(image, &GAUSSIAN_3X3, border, dst)
+}
+
+/// Like [`gaussian_5x5_border`], but writes into `dst`; see [`apply_kernel_border_into`].
+pub fn gaussian_5x5_border_into(
+    image: &ImageView<Gray<f32>>,
+    border: Border<Gray<f32>>,
+    dst: &mut ImageViewMut<Gray<f32>>,
+) -> Result<(), ImageError> {
+    apply_kernel_border_into(image, &GAUSSIAN_5X5, border, dst)
+}
+
+/// Like [`gaussian`], but writes into `dst`; see [`apply_separable_into`].
+pub fn gaussian_into(
+    image: &ImageView<Gray<f32>>,
+    sigma: f32,
+    border: Border<Gray<f32>>,
+    scratch: &mut ImageViewMut<Gray<f32>>,
+    dst: &mut ImageViewMut<Gray<f32>>,
+) -> Result<(), ImageError> {
+    apply_separable_into(image, &SeparableKernel::gaussian(sigma), border, scratch, dst)
+}
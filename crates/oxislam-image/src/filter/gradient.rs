@@ -0,0 +1,62 @@
+use super::sobel::sobel_border;
+use crate::border::Border;
+use crate::image::{Image, ImageView, map2};
+use crate::pixel::Gray;
+
+/// Per-pixel gradient magnitude `sqrt(ix² + iy²)`.
+///
+/// # Panics
+///
+/// Panics if `ix` and `iy` differ in size.
+pub fn gradient_magnitude(
+    ix: &ImageView<Gray<f32>>,
+    iy: &ImageView<Gray<f32>>,
+) -> Image<Gray<f32>> {
+    map2(ix, iy, |gx, gy| Gray::new(gx.value.hypot(gy.value)))
+}
+
+/// Per-pixel gradient orientation `atan2(iy, ix)` in radians, within `[-π, π]`.
+///
+/// With image coordinates (y pointing down), positive angles turn clockwise from the x axis.
+///
+/// # Panics
+///
+/// Panics if `ix` and `iy` differ in size.
+pub fn gradient_orientation(
+    ix: &ImageView<Gray<f32>>,
+    iy: &ImageView<Gray<f32>>,
+) -> Image<Gray<f32>> {
+    map2(ix, iy, |gx, gy| Gray::new(gy.value.atan2(gx.value)))
+}
+
+/// Sobel gradient magnitude and orientation, with the same size as the input.
+pub fn gradient_magnitude_orientation(
+    image: &ImageView<Gray<f32>>,
+    border: Border<Gray<f32>>,
+) -> (Image<Gray<f32>>, Image<Gray<f32>>) {
+    let (ix, iy) = sobel_border(image, border);
+    (gradient_magnitude(&ix.view(), &iy.view()), gradient_orientation(&ix.view(), &iy.view()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use super::*;
+
+    #[test]
+    fn magnitude_and_orientation_of_ramps() {
+        // Brightness increasing downwards
+        let data: Vec<Gray<f32>> = (0..25).map(|v| Gray::new((v / 5) as f32)).collect();
+        let img = Image::new(5, 5, 5, data);
+        let (mag, ori) = gradient_magnitude_orientation(&img.view(), Border::Replicate);
+
+        assert_eq!(mag.get(2, 2).value, 8.0);
+        assert!((ori.get(2, 2).value - FRAC_PI_2).abs() < 1e-6);
+
+        let ix = Image::new(1, 1, 1, vec![Gray::new(3.0)]);
+        let iy = Image::new(1, 1, 1, vec![Gray::new(-4.0)]);
+        assert_eq!(gradient_magnitude(&ix.view(), &iy.view()).get(0, 0).value, 5.0);
+        assert!(gradient_orientation(&ix.view(), &iy.view()).get(0, 0).value < 0.0);
+    }
+}
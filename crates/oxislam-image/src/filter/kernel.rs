@@ -1,4 +1,6 @@
-use crate::image::{Image, ImageView};
+use crate::border::Border;
+use crate::error::ImageError;
+use crate::image::{Image, ImageView, ImageViewMut, check_size, fill_with};
 use crate::parallel::par_row_collect;
 use crate::pixel::Gray;
 
@@ -19,21 +21,108 @@ fn compute_pixel<const N: usize>(
     Gray::new(sum)
 }
 
+/// # Panics
+///
+/// Panics if the image is smaller than the kernel; see [`try_apply_kernel`].
 pub fn apply_kernel<const N: usize>(
     image: &ImageView<Gray<f32>>,
     kernel: &Kernel<N>,
 ) -> Image<Gray<f32>> {
+    try_apply_kernel(image, kernel).unwrap_or_else(|e| panic!("{e}"))
+}
+
+/// Convolve without padding, shrinking the output by `N - 1` in each dimension.
+pub fn try_apply_kernel<const N: usize>(
+    image: &ImageView<Gray<f32>>,
+    kernel: &Kernel<N>,
+) -> Result<Image<Gray<f32>>, ImageError> {
     let w = image.width();
     let h = image.height();
 
-    assert!(w >= N && h >= N, "Image must be at least {N}x{N}");
+    if w < N || h < N {
+        return Err(ImageError::TooSmall { width: w, height: h, min_width: N, min_height: N });
+    }
 
     let out_w = w - (N - 1);
     let out_h = h - (N - 1);
 
     let data = par_row_collect(out_w, out_h, |x, y| compute_pixel(image, kernel, x, y));
 
-    Image::new(out_w, out_h, out_w, data)
+    Ok(Image::new(out_w, out_h, out_w, data))
+}
+
+/// Like [`try_apply_kernel`], but writes into `dst`, which must be `N - 1` smaller than the
+/// input in each dimension.
+pub fn apply_kernel_into<const N: usize>(
+    image: &ImageView<Gray<f32>>,
+    kernel: &Kernel<N>,
+    dst: &mut ImageViewMut<Gray<f32>>,
+) -> Result<(), ImageError> {
+    let w = image.width();
+    let h = image.height();
+
+    if w < N || h < N {
+        return Err(ImageError::TooSmall { width: w, height: h, min_width: N, min_height: N });
+    }
+    check_size(dst, w - (N - 1), h - (N - 1))?;
+
+    fill_with(dst, |x, y| compute_pixel(image, kernel, x, y));
+    Ok(())
+}
+
+fn compute_pixel_border<const N: usize>(
+    image: &ImageView<Gray<f32>>,
+    kernel: &Kernel<N>,
+    border: Border<Gray<f32>>,
+    x: usize,
+    y: usize,
+) -> Gray<f32> {
+    let r = N / 2;
+    if x >= r && y >= r && x + r < image.width() && y + r < image.height() {
+        return compute_pixel(image, kernel, x - r, y - r);
+    }
+
+    let mut sum = 0.0;
+    for (ky, kernel_row) in kernel.iter().enumerate() {
+        let sy = (y + ky) as isize - r as isize;
+        for (kx, kernel_val) in kernel_row.iter().enumerate() {
+            let sx = (x + kx) as isize - r as isize;
+            sum += image.get_border(sx, sy, border).value * kernel_val;
+        }
+    }
+    Gray::new(sum)
+}
+
+/// Convolve with a kernel centered on each pixel, producing an output of the same size as the
+/// input. Pixels outside the image are extrapolated according to `border`.
+pub fn apply_kernel_border<const N: usize>(
+    image: &ImageView<Gray<f32>>,
+    kernel: &Kernel<N>,
+    border: Border<Gray<f32>>,
+) -> Image<Gray<f32>> {
+    assert!(N % 2 == 1, "Kernel size must be odd");
+
+    let w = image.width();
+    let h = image.height();
+
+    let data = par_row_collect(w, h, |x, y| compute_pixel_border(image, kernel, border, x, y));
+
+    Image::new(w, h, w, data)
+}
+
+/// Like [`apply_kernel_border`], but writes into `dst`, which must have the same size as the
+/// input.
+pub fn apply_kernel_border_into<const N: usize>(
+    image: &ImageView<Gray<f32>>,
+    kernel: &Kernel<N>,
+    border: Border<Gray<f32>>,
+    dst: &mut ImageViewMut<Gray<f32>>,
+) -> Result<(), ImageError> {
+    assert!(N % 2 == 1, "Kernel size must be odd");
+    check_size(dst, image.width(), image.height())?;
+
+    fill_with(dst, |x, y| compute_pixel_border(image, kernel, border, x, y));
+    Ok(())
 }
 
 #[cfg(test)]
@@ -61,4 +150,70 @@ mod tests {
         assert_eq!(out.get(0, 1).value, 10.0);
         assert_eq!(out.get(1, 1).value, 11.0);
     }
+
+    #[test]
+    fn convolution_rejects_small_image() {
+        let img = Image::filled(2, 4, Gray::new(0.0f32));
+        let identity: Kernel<3> = [[0.0; 3]; 3];
+
+        assert_eq!(
+            try_apply_kernel(&img.view(), &identity).unwrap_err(),
+            ImageError::TooSmall { width: 2, height: 4, min_width: 3, min_height: 3 }
+        );
+    }
+
+    #[test]
+    fn convolution_border_modes() {
+        // [1, 2, 3]
+        // [4, 5, 6]
+        let data: Vec<Gray<f32>> = (1..=6).map(|v| Gray::new(v as f32)).collect();
+        let img = Image::new(3, 2, 3, data);
+
+        // Picks the pixel to the upper-left of the center
+        #[rustfmt::skip]
+        let shift: Kernel<3> = [
+            [1.0, 0.0, 0.0],
+            [0.0, 0.0, 0.0],
+            [0.0, 0.0, 0.0],
+        ];
+
+        let row = |out: &Image<Gray<f32>>| (0..3).map(|x| out.get(x, 1).value).collect::<Vec<_>>();
+
+        let out = apply_kernel_border(&img.view(), &shift, Border::Constant(Gray::new(-1.0)));
+        assert_eq!((out.width(), out.height()), (3, 2));
+        assert_eq!(out.get(0, 0).value, -1.0);
+        assert_eq!(row(&out), [-1.0, 1.0, 2.0]);
+
+        let out = apply_kernel_border(&img.view(), &shift, Border::Replicate);
+        assert_eq!(out.get(0, 0).value, 1.0);
+        assert_eq!(row(&out), [1.0, 1.0, 2.0]);
+
+        let out = apply_kernel_border(&img.view(), &shift, Border::Reflect101);
+        assert_eq!(out.get(0, 0).value, 5.0);
+        assert_eq!(row(&out), [2.0, 1.0, 2.0]);
+
+        let out = apply_kernel_border(&img.view(), &shift, Border::Wrap);
+        assert_eq!(out.get(0, 0).value, 6.0);
+        assert_eq!(row(&out), [3.0, 1.0, 2.0]);
+    }
+
+    #[test]
+    fn convolution_into_matches_allocating() {
+        let data: Vec<Gray<f32>> = (0..20).map(|v| Gray::new((v % 7) as f32)).collect();
+        let img = Image::new(5, 4, 5, data);
+        let kernel: Kernel<3> = [[0.1, 0.2, 0.3], [0.4, 0.5, 0.6], [0.7, 0.8, 0.9]];
+
+        let mut out = Image::filled(3, 2, Gray::new(0.0));
+        apply_kernel_into(&img.view(), &kernel, &mut out.view_mut()).unwrap();
+        assert_eq!(out.data(), apply_kernel(&img.view(), &kernel).data());
+
+        let mut out = Image::filled(5, 4, Gray::new(0.0));
+        apply_kernel_border_into(&img.view(), &kernel, Border::Wrap, &mut out.view_mut()).unwrap();
+        assert_eq!(out.data(), apply_kernel_border(&img.view(), &kernel, Border::Wrap).data());
+
+        assert_eq!(
+            apply_kernel_into(&img.view(), &kernel, &mut out.view_mut()).unwrap_err(),
+            ImageError::SizeMismatch { expected: (3, 2), actual: (5, 4) }
+        );
+    }
 }
@@ -0,0 +1,132 @@
+use std::cmp::Ordering;
+
+use crate::border::Border;
+use crate::image::{Image, ImageView};
+use crate::parallel::par_rows_mut;
+use crate::pixel::{Channel, Gray};
+
+/// Median over the `(2 * radius + 1)`-sized square window around each pixel.
+///
+/// Sorts every window, so the cost grows quadratically with `radius`; prefer [`median_u8`] for
+/// 8-bit images and larger radii.
+pub fn median<T: Channel>(
+    image: &ImageView<Gray<T>>,
+    radius: usize,
+    border: Border<Gray<T>>,
+) -> Image<Gray<T>> {
+    let (w, h) = (image.width(), image.height());
+    let r = radius as isize;
+    let mut out = Image::filled(w, h, *image.get(0, 0));
+
+    par_rows_mut(out.view_mut().data_mut(), w, h, w, |y, row| {
+        let mut window = Vec::with_capacity((2 * radius + 1).pow(2));
+        for (x, px) in row.iter_mut().enumerate() {
+            window.clear();
+            for sy in y as isize - r..=y as isize + r {
+                for sx in x as isize - r..=x as isize + r {
+                    window.push(image.get_border(sx, sy, border).value);
+                }
+            }
+            let mid = window.len() / 2;
+            let (_, m, _) = window
+                .select_nth_unstable_by(mid, |a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+            *px = Gray::new(*m);
+        }
+    });
+    out
+}
+
+pub fn median_3x3<T: Channel>(
+    image: &ImageView<Gray<T>>,
+    border: Border<Gray<T>>,
+) -> Image<Gray<T>> {
+    median(image, 1, border)
+}
+
+pub fn median_5x5<T: Channel>(
+    image: &ImageView<Gray<T>>,
+    border: Border<Gray<T>>,
+) -> Image<Gray<T>> {
+    median(image, 2, border)
+}
+
+/// Like [`median`], but keeps a sliding 256-bin histogram along each row, so the cost per pixel
+/// grows only linearly with `radius`.
+pub fn median_u8(
+    image: &ImageView<Gray<u8>>,
+    radius: usize,
+    border: Border<Gray<u8>>,
+) -> Image<Gray<u8>> {
+    let (w, h) = (image.width(), image.height());
+    let r = radius as isize;
+    let target = ((2 * radius + 1).pow(2) / 2) as u32;
+    let mut out = Image::filled(w, h, Gray::new(0));
+
+    par_rows_mut(out.view_mut().data_mut(), w, h, w, |y, row| {
+        let y = y as isize;
+        let column =
+            |x: isize| (y - r..=y + r).map(move |sy| image.get_border(x, sy, border).value);
+
+        let mut hist = [0u32; 256];
+        for v in (-r..=r).flat_map(column) {
+            hist[v as usize] += 1;
+        }
+
+        // `below` counts the window values smaller than the current median estimate `med`
+        let (mut med, mut below) = (0usize, 0u32);
+        for (x, px) in row.iter_mut().enumerate() {
+            if x > 0 {
+                let x = x as isize;
+                for v in column(x - r - 1).map(usize::from) {
+                    hist[v] -= 1;
+                    below -= u32::from(v < med);
+                }
+                for v in column(x + r).map(usize::from) {
+                    hist[v] += 1;
+                    below += u32::from(v < med);
+                }
+            }
+
+            while below > target {
+                med -= 1;
+                below -= hist[med];
+            }
+            while below + hist[med] <= target {
+                below += hist[med];
+                med += 1;
+            }
+            *px = Gray::new(med as u8);
+        }
+    });
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn median_removes_impulse_noise() {
+        let mut data = vec![Gray::new(0.5f32); 25];
+        data[12] = Gray::new(100.0);
+        data[0] = Gray::new(-100.0);
+        let img = Image::new(5, 5, 5, data);
+
+        let out = median_3x3(&img.view(), Border::Reflect101);
+        assert!(out.view().pixels().all(|p| p.value == 0.5));
+    }
+
+    #[test]
+    fn histogram_median_matches_sorting() {
+        let data: Vec<Gray<u8>> = (0..11 * 9).map(|v| Gray::new(((v * 37) % 251) as u8)).collect();
+        let img = Image::new(11, 9, 11, data);
+
+        for radius in [0, 1, 2, 4] {
+            for border in [Border::Replicate, Border::Reflect101, Border::Constant(Gray::new(7))] {
+                let fast = median_u8(&img.view(), radius, border);
+                let slow = median(&img.view(), radius, border);
+                assert_eq!(fast.data(), slow.data(), "radius {radius}, border {border:?}");
+            }
+        }
+    }
+}
@@ -1,7 +1,40 @@
+pub mod bilateral;
+pub mod box_filter;
+pub mod canny;
+pub mod derivative;
 pub mod gaussian;
+pub mod gradient;
 pub mod kernel;
+pub mod median;
+pub mod morphology;
+pub mod separable;
 pub mod sobel;
+pub mod structure_tensor;
 
-pub use gaussian::{gaussian_3x3, gaussian_5x5};
-pub use kernel::{Kernel, apply_kernel};
-pub use sobel::sobel;
+pub use bilateral::bilateral;
+pub use box_filter::{
+    box_filter, box_filter_into, box_mean, box_mean_into, local_mean_variance,
+    local_mean_variance_into,
+};
+pub use canny::{EdgePixel, canny, canny_edges};
+pub use derivative::{
+    Hessian, laplacian, laplacian_of_gaussian, scharr, scharr_kernel, second_derivatives,
+    sobel_aperture, sobel_kernel, sobel_kernel_1d,
+};
+pub use gaussian::{
+    gaussian, gaussian_3x3, gaussian_3x3_border, gaussian_3x3_border_into, gaussian_3x3_into,
+    gaussian_5x5, gaussian_5x5_border, gaussian_5x5_border_into, gaussian_5x5_into, gaussian_into,
+};
+pub use gradient::{gradient_magnitude, gradient_magnitude_orientation, gradient_orientation};
+pub use kernel::{
+    Kernel, apply_kernel, apply_kernel_border, apply_kernel_border_into, apply_kernel_into,
+    try_apply_kernel,
+};
+pub use median::{median, median_3x3, median_5x5, median_u8};
+pub use morphology::{StructuringElement, close, dilate, erode, open};
+pub use separable::{
+    SeparableKernel, apply_separable, apply_separable_into, convolve_cols, convolve_cols_into,
+    convolve_rows, convolve_rows_into, gaussian_kernel_1d,
+};
+pub use sobel::{sobel, sobel_border, sobel_border_into, sobel_into};
+pub use structure_tensor::StructureTensor;
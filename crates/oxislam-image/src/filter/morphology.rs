@@ -0,0 +1,194 @@
+use crate::border::Border;
+use crate::image::{Image, ImageView};
+use crate::parallel::par_row_collect;
+use crate::pixel::{Channel, Gray};
+
+/// Binary neighborhood shape for morphological operations, centered on the output pixel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructuringElement {
+    width: usize,
+    height: usize,
+    mask: Vec<bool>,
+}
+
+impl StructuringElement {
+    /// Element from a row-major `width`x`height` mask.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a dimension is even, the mask length does not match or the mask is empty.
+    pub fn new(width: usize, height: usize, mask: Vec<bool>) -> Self {
+        assert!(width % 2 == 1 && height % 2 == 1, "Element dimensions must be odd");
+        assert_eq!(mask.len(), width * height, "Mask length must be width * height");
+        assert!(mask.contains(&true), "Element must contain at least one pixel");
+        Self { width, height, mask }
+    }
+
+    /// Filled `width`x`height` rectangle.
+    pub fn rect(width: usize, height: usize) -> Self {
+        Self::new(width, height, vec![true; width * height])
+    }
+
+    /// Ellipse inscribed in a `width`x`height` box, touching the centers of its edge pixels.
+    pub fn ellipse(width: usize, height: usize) -> Self {
+        let (rx, ry) = ((width / 2) as f32, (height / 2) as f32);
+        // A zero semi-axis degenerates to a line along the other axis
+        let norm = |d: f32, r: f32| {
+            if r > 0.0 {
+                (d / r).powi(2)
+            } else if d == 0.0 {
+                0.0
+            } else {
+                f32::INFINITY
+            }
+        };
+        let mask = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x as f32 - rx, y as f32 - ry)))
+            .map(|(dx, dy)| norm(dx, rx) + norm(dy, ry) <= 1.0)
+            .collect();
+        Self::new(width, height, mask)
+    }
+
+    #[inline]
+    pub fn width(&self) -> usize { self.width }
+
+    #[inline]
+    pub fn height(&self) -> usize { self.height }
+
+    /// # Panics
+    ///
+    /// Panics if `(x, y)` is out of bounds.
+    #[inline]
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        assert!(x < self.width && y < self.height, "Element index out of bounds");
+        self.mask[y * self.width + x]
+    }
+
+    /// Offsets of the element's pixels relative to its center.
+    fn offsets(&self) -> Vec<(isize, isize)> {
+        let (cx, cy) = ((self.width / 2) as isize, (self.height / 2) as isize);
+        (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+            .filter(|&(x, y)| self.contains(x, y))
+            .map(|(x, y)| (x as isize - cx, y as isize - cy))
+            .collect()
+    }
+}
+
+/// Extremum over `offsets`, where `better(a, b)` tells whether `a` should replace `b`.
+fn extremum<T: Channel>(
+    image: &ImageView<Gray<T>>,
+    offsets: &[(isize, isize)],
+    border: Border<Gray<T>>,
+    better: fn(T, T) -> bool,
+) -> Image<Gray<T>> {
+    let (w, h) = (image.width(), image.height());
+    let data = par_row_collect(w, h, |x, y| {
+        let values = offsets
+            .iter()
+            .map(|&(dx, dy)| image.get_border(x as isize + dx, y as isize + dy, border).value);
+        Gray::new(values.reduce(|a, b| if better(b, a) { b } else { a }).unwrap())
+    });
+    Image::new(w, h, w, data)
+}
+
+fn morph<T: Channel>(
+    image: &ImageView<Gray<T>>,
+    element: &StructuringElement,
+    border: Border<Gray<T>>,
+    better: fn(T, T) -> bool,
+) -> Image<Gray<T>> {
+    if element.mask.iter().all(|&m| m) {
+        // Rectangles are separable into a row pass and a column pass
+        let (rx, ry) = ((element.width / 2) as isize, (element.height / 2) as isize);
+        let row: Vec<_> = (-rx..=rx).map(|dx| (dx, 0)).collect();
+        let col: Vec<_> = (-ry..=ry).map(|dy| (0, dy)).collect();
+        let rows = extremum(image, &row, border, better);
+        extremum(&rows.view(), &col, border, better)
+    } else {
+        extremum(image, &element.offsets(), border, better)
+    }
+}
+
+/// Minimum over the structuring element around each pixel.
+///
+/// [`Border::Replicate`] keeps the image border from eroding inwards.
+pub fn erode<T: Channel>(
+    image: &ImageView<Gray<T>>,
+    element: &StructuringElement,
+    border: Border<Gray<T>>,
+) -> Image<Gray<T>> {
+    morph(image, element, border, |a, b| a < b)
+}
+
+/// Maximum over the structuring element around each pixel.
+pub fn dilate<T: Channel>(
+    image: &ImageView<Gray<T>>,
+    element: &StructuringElement,
+    border: Border<Gray<T>>,
+) -> Image<Gray<T>> {
+    morph(image, element, border, |a, b| a > b)
+}
+
+/// Erosion followed by dilation, removing bright features smaller than the element.
+pub fn open<T: Channel>(
+    image: &ImageView<Gray<T>>,
+    element: &StructuringElement,
+    border: Border<Gray<T>>,
+) -> Image<Gray<T>> {
+    dilate(&erode(image, element, border).view(), element, border)
+}
+
+/// Dilation followed by erosion, filling dark features smaller than the element.
+pub fn close<T: Channel>(
+    image: &ImageView<Gray<T>>,
+    element: &StructuringElement,
+    border: Border<Gray<T>>,
+) -> Image<Gray<T>> {
+    erode(&dilate(image, element, border).view(), element, border)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mask(element: &StructuringElement) -> Vec<u8> {
+        (0..element.height())
+            .flat_map(|y| (0..element.width()).map(move |x| (x, y)))
+            .map(|(x, y)| u8::from(element.contains(x, y)))
+            .collect()
+    }
+
+    #[test]
+    fn ellipse_shapes() {
+        assert_eq!(mask(&StructuringElement::ellipse(3, 3)), [0, 1, 0, 1, 1, 1, 0, 1, 0]);
+        #[rustfmt::skip]
+        let expected = [
+            0, 0, 1, 0, 0,
+            1, 1, 1, 1, 1,
+            0, 0, 1, 0, 0,
+        ];
+        assert_eq!(mask(&StructuringElement::ellipse(5, 3)), expected);
+        assert_eq!(mask(&StructuringElement::ellipse(1, 3)), [1, 1, 1]);
+    }
+
+    #[test]
+    fn erode_dilate_single_pixel() {
+        let mut data = vec![Gray::new(0u8); 49];
+        data[3 * 7 + 3] = Gray::new(9);
+        let img = Image::new(7, 7, 7, data);
+        let rect = StructuringElement::rect(3, 3);
+        let cross = StructuringElement::ellipse(3, 3);
+
+        let dilated = dilate(&img.view(), &rect, Border::Replicate);
+        assert_eq!(dilated.view().pixels().filter(|p| p.value == 9).count(), 9);
+        let dilated = dilate(&img.view(), &cross, Border::Replicate);
+        assert_eq!(dilated.view().pixels().filter(|p| p.value == 9).count(), 5);
+        assert_eq!(dilated.get(2, 2).value, 0);
+
+        // Opening removes the speck, closing keeps it
+        assert!(open(&img.view(), &rect, Border::Replicate).view().pixels().all(|p| p.value == 0));
+        assert_eq!(close(&img.view(), &rect, Border::Replicate).data(), img.data());
+        assert_eq!(erode(&dilated.view(), &cross, Border::Replicate).data(), img.data());
+    }
+}
@@ -0,0 +1,236 @@
+use crate::border::Border;
+use crate::error::ImageError;
+use crate::image::{Image, ImageView, ImageViewMut, check_size, fill_with};
+use crate::parallel::par_row_collect;
+use crate::pixel::Gray;
+
+/// A 2D kernel expressed as the outer product of a row kernel `x` and a column kernel `y`.
+///
+/// Both kernels must have odd length and are centered on the output pixel.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeparableKernel {
+    pub x: Vec<f32>,
+    pub y: Vec<f32>,
+}
+
+impl SeparableKernel {
+    pub fn new(x: Vec<f32>, y: Vec<f32>) -> Self {
+        assert!(x.len() % 2 == 1, "Row kernel length must be odd");
+        assert!(y.len() % 2 == 1, "Column kernel length must be odd");
+        Self { x, y }
+    }
+
+    /// Isotropic Gaussian with standard deviation `sigma`.
+    pub fn gaussian(sigma: f32) -> Self {
+        let k = gaussian_kernel_1d(sigma);
+        Self { x: k.clone(), y: k }
+    }
+}
+
+/// Normalized 1D Gaussian kernel with radius `ceil(3 * sigma)`.
+pub fn gaussian_kernel_1d(sigma: f32) -> Vec<f32> {
+    assert!(sigma > 0.0, "Sigma must be positive");
+
+    let radius = (3.0 * sigma).ceil() as isize;
+    let denom = 2.0 * sigma * sigma;
+    let mut kernel: Vec<f32> =
+        (-radius..=radius).map(|i| (-((i * i) as f32) / denom).exp()).collect();
+
+    let sum: f32 = kernel.iter().sum();
+    for v in &mut kernel {
+        *v /= sum;
+    }
+    kernel
+}
+
+#[inline]
+fn correlate_row(
+    image: &ImageView<Gray<f32>>,
+    kernel: &[f32],
+    border: Border<Gray<f32>>,
+    x: usize,
+    y: usize,
+) -> Gray<f32> {
+    let r = kernel.len() / 2;
+    let mut sum = 0.0;
+    if x >= r && x + r < image.width() {
+        for (k, kernel_val) in kernel.iter().enumerate() {
+            sum += image.get(x + k - r, y).value * kernel_val;
+        }
+    } else {
+        for (k, kernel_val) in kernel.iter().enumerate() {
+            let sx = (x + k) as isize - r as isize;
+            sum += image.get_border(sx, y as isize, border).value * kernel_val;
+        }
+    }
+    Gray::new(sum)
+}
+
+#[inline]
+fn correlate_col(
+    image: &ImageView<Gray<f32>>,
+    kernel: &[f32],
+    border: Border<Gray<f32>>,
+    x: usize,
+    y: usize,
+) -> Gray<f32> {
+    let r = kernel.len() / 2;
+    let mut sum = 0.0;
+    if y >= r && y + r < image.height() {
+        for (k, kernel_val) in kernel.iter().enumerate() {
+            sum += image.get(x, y + k - r).value * kernel_val;
+        }
+    } else {
+        for (k, kernel_val) in kernel.iter().enumerate() {
+            let sy = (y + k) as isize - r as isize;
+            sum += image.get_border(x as isize, sy, border).value * kernel_val;
+        }
+    }
+    Gray::new(sum)
+}
+
+/// Correlate every row with a centered 1D kernel, keeping the image size.
+pub fn convolve_rows(
+    image: &ImageView<Gray<f32>>,
+    kernel: &[f32],
+    border: Border<Gray<f32>>,
+) -> Image<Gray<f32>> {
+    assert!(kernel.len() % 2 == 1, "Kernel length must be odd");
+
+    let w = image.width();
+    let h = image.height();
+    let data = par_row_collect(w, h, |x, y| correlate_row(image, kernel, border, x, y));
+    Image::new(w, h, w, data)
+}
+
+/// Correlate every column with a centered 1D kernel, keeping the image size.
+pub fn convolve_cols(
+    image: &ImageView<Gray<f32>>,
+    kernel: &[f32],
+    border: Border<Gray<f32>>,
+) -> Image<Gray<f32>> {
+    assert!(kernel.len() % 2 == 1, "Kernel length must be odd");
+
+    let w = image.width();
+    let h = image.height();
+    let data = par_row_collect(w, h, |x, y| correlate_col(image, kernel, border, x, y));
+    Image::new(w, h, w, data)
+}
+
+/// Like [`convolve_rows`], but writes into `dst`, which must have the same size as the input.
+pub fn convolve_rows_into(
+    image: &ImageView<Gray<f32>>,
+    kernel: &[f32],
+    border: Border<Gray<f32>>,
+    dst: &mut ImageViewMut<Gray<f32>>,
+) -> Result<(), ImageError> {
+    assert!(kernel.len() % 2 == 1, "Kernel length must be odd");
+    check_size(dst, image.width(), image.height())?;
+
+    fill_with(dst, |x, y| correlate_row(image, kernel, border, x, y));
+    Ok(())
+}
+
+/// Like [`convolve_cols`], but writes into `dst`, which must have the same size as the input.
+pub fn convolve_cols_into(
+    image: &ImageView<Gray<f32>>,
+    kernel: &[f32],
+    border: Border<Gray<f32>>,
+    dst: &mut ImageViewMut<Gray<f32>>,
+) -> Result<(), ImageError> {
+    assert!(kernel.len() % 2 == 1, "Kernel length must be odd");
+    check_size(dst, image.width(), image.height())?;
+
+    fill_with(dst, |x, y| correlate_col(image, kernel, border, x, y));
+    Ok(())
+}
+
+/// Apply a separable kernel as a row pass followed by a column pass.
+pub fn apply_separable(
+    image: &ImageView<Gray<f32>>,
+    kernel: &SeparableKernel,
+    border: Border<Gray<f32>>,
+) -> Image<Gray<f32>> {
+    let rows = convolve_rows(image, &kernel.x, border);
+    convolve_cols(&rows.view(), &kernel.y, border)
+}
+
+/// Like [`apply_separable`], but writes into `dst`. The intermediate row pass goes through
+/// `scratch`; both must have the same size as the input.
+pub fn apply_separable_into(
+    image: &ImageView<Gray<f32>>,
+    kernel: &SeparableKernel,
+    border: Border<Gray<f32>>,
+    scratch: &mut ImageViewMut<Gray<f32>>,
+    dst: &mut ImageViewMut<Gray<f32>>,
+) -> Result<(), ImageError> {
+    convolve_rows_into(image, &kernel.x, border, scratch)?;
+    convolve_cols_into(&scratch.view(), &kernel.y, border, dst)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filter::{apply_kernel_border, gaussian_3x3_border};
+
+    #[test]
+    fn gaussian_kernel_is_normalized_and_symmetric() {
+        for sigma in [0.5, 1.0, 1.6, 3.2] {
+            let k = gaussian_kernel_1d(sigma);
+            assert_eq!(k.len(), 2 * (3.0 * sigma).ceil() as usize + 1);
+            assert!((k.iter().sum::<f32>() - 1.0).abs() < 1e-5);
+            for i in 0..k.len() / 2 {
+                assert_eq!(k[i], k[k.len() - 1 - i]);
+            }
+        }
+    }
+
+    #[test]
+    fn separable_matches_2d_kernel() {
+        let data: Vec<Gray<f32>> = (0..35).map(|v| Gray::new(((v * 7) % 11) as f32)).collect();
+        let img = Image::new(7, 5, 7, data);
+
+        let binomial = SeparableKernel::new(vec![0.25, 0.5, 0.25], vec![0.25, 0.5, 0.25]);
+        let separable = apply_separable(&img.view(), &binomial, Border::Reflect101);
+        let full = gaussian_3x3_border(&img.view(), Border::Reflect101);
+
+        for (a, b) in separable.view().pixels().zip(full.view().pixels()) {
+            assert!((a.value - b.value).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn separable_asymmetric_axes() {
+        let data: Vec<Gray<f32>> = (0..20).map(|v| Gray::new(v as f32)).collect();
+        let img = Image::new(5, 4, 5, data);
+
+        let kernel = SeparableKernel::new(vec![-1.0, 0.0, 1.0], vec![1.0, 2.0, 1.0]);
+        let separable = apply_separable(&img.view(), &kernel, Border::Replicate);
+
+        #[rustfmt::skip]
+        let full = apply_kernel_border(&img.view(), &[
+            [-1.0, 0.0, 1.0],
+            [-2.0, 0.0, 2.0],
+            [-1.0, 0.0, 1.0],
+        ], Border::Replicate);
+
+        for (a, b) in separable.view().pixels().zip(full.view().pixels()) {
+            assert!((a.value - b.value).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn separable_into_reuses_buffers() {
+        let data: Vec<Gray<f32>> = (0..30).map(|v| Gray::new(((v * 5) % 9) as f32)).collect();
+        let img = Image::new(6, 5, 6, data);
+        let kernel = SeparableKernel::gaussian(1.0);
+
+        let mut scratch = Image::filled(6, 5, Gray::new(0.0));
+        let mut out = Image::filled(6, 5, Gray::new(0.0));
+        let (mut s, mut o) = (scratch.view_mut(), out.view_mut());
+        apply_separable_into(&img.view(), &kernel, Border::Reflect, &mut s, &mut o).unwrap();
+
+        let expected = apply_separable(&img.view(), &kernel, Border::Reflect);
+        assert_eq!(out.data(), expected.data());
+    }
+}
@@ -1,5 +1,9 @@
-use super::kernel::{Kernel, apply_kernel};
-use crate::image::{Image, ImageView};
+use super::kernel::{
+    Kernel, apply_kernel, apply_kernel_border, apply_kernel_border_into, apply_kernel_into,
+};
+use crate::border::Border;
+use crate::error::ImageError;
+use crate::image::{Image, ImageView, ImageViewMut, check_size};
 use crate::pixel::Gray;
 
 #[rustfmt::skip]
@@ -22,6 +26,47 @@ pub fn sobel(image: &ImageView<Gray<f32>>) -> (Image<Gray<f32>>, Image<Gray<f32>
     (ix, iy)
 }
 
+pub fn sobel_border(
+    image: &ImageView<Gray<f32>>,
+    border: Border<Gray<f32>>,
+) -> (Image<Gray<f32>>, Image<Gray<f32>>) {
+    let ix = apply_kernel_border(image, &SOBEL_X, border);
+    let iy = apply_kernel_border(image, &SOBEL_Y, border);
+    (ix, iy)
+}
+
+/// Like [`sobel`], but writes into `ix` and `iy`; see [`apply_kernel_into`].
+///
+/// Both outputs are checked before either is written, so on error neither is modified.
+pub fn sobel_into(
+    image: &ImageView<Gray<f32>>,
+    ix: &mut ImageViewMut<Gray<f32>>,
+    iy: &mut ImageViewMut<Gray<f32>>,
+) -> Result<(), ImageError> {
+    // Too small inputs are reported by `apply_kernel_into` before anything is written
+    let (w, h) = (image.width(), image.height());
+    if w >= 3 && h >= 3 {
+        check_size(ix, w - 2, h - 2)?;
+        check_size(iy, w - 2, h - 2)?;
+    }
+    apply_kernel_into(image, &SOBEL_X, ix)?;
+    apply_kernel_into(image, &SOBEL_Y, iy)
+}
+
+/// Like [`sobel_border`], but writes into `ix` and `iy`, which must have the same size as the
+/// input. On error neither output is modified.
+pub fn sobel_border_into(
+    image: &ImageView<Gray<f32>>,
+    border: Border<Gray<f32>>,
+    ix: &mut ImageViewMut<Gray<f32>>,
+    iy: &mut ImageViewMut<Gray<f32>>,
+) -> Result<(), ImageError> {
+    check_size(ix, image.width(), image.height())?;
+    check_size(iy, image.width(), image.height())?;
+    apply_kernel_border_into(image, &SOBEL_X, border, ix)?;
+    apply_kernel_border_into(image, &SOBEL_Y, border, iy)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -42,4 +87,37 @@ mod tests {
         assert_eq!(ix.get(0, 0).value, 4.0); // strong horizontal gradient
         assert_eq!(iy.get(0, 0).value, 0.0); // no vertical gradient
     }
+
+    #[test]
+    fn sobel_border_keeps_size() {
+        let data: Vec<Gray<f32>> =
+            (0..3).flat_map(|_| [0.0, 0.0, 1.0, 1.0]).map(Gray::new).collect();
+        let img = Image::new(4, 3, 4, data);
+
+        let (ix, iy) = sobel_border(&img.view(), Border::Replicate);
+        assert_eq!(ix.width(), 4);
+        assert_eq!(ix.height(), 3);
+        // Output coordinates match input coordinates
+        assert_eq!(ix.get(1, 1).value, 4.0);
+        assert_eq!(ix.get(2, 1).value, 4.0);
+        assert_eq!(ix.get(0, 1).value, 0.0);
+        assert_eq!(ix.get(3, 0).value, 0.0);
+        assert!(iy.view().pixels().all(|p| p.value == 0.0));
+    }
+
+    #[test]
+    fn sobel_into_leaves_outputs_untouched_on_error() {
+        let img = Image::filled(4, 3, Gray::new(1.0f32));
+        let mut ix = Image::filled(4, 3, Gray::new(7.0));
+        let mut iy = Image::filled(3, 3, Gray::new(7.0));
+
+        let result = sobel_border_into(
+            &img.view(),
+            Border::Replicate,
+            &mut ix.view_mut(),
+            &mut iy.view_mut(),
+        );
+        assert!(matches!(result, Err(ImageError::SizeMismatch { .. })));
+        assert!(ix.view().pixels().all(|p| p.value == 7.0));
+    }
 }
@@ -0,0 +1,194 @@
+use super::gaussian::gaussian;
+use super::separable::{SeparableKernel, apply_separable_into};
+use super::sobel::sobel_border;
+use crate::border::Border;
+use crate::error::ImageError;
+use crate::image::{Image, ImageView, ImageViewMut, check_size, fill_with, mul_into};
+use crate::pixel::Gray;
+use crate::pool::ImagePool;
+
+/// Smoothed second-moment matrix `[[Sxx, Sxy], [Sxy, Syy]]` of the image gradients at every
+/// pixel, the basis of Harris and Shi-Tomasi corner measures.
+#[derive(Debug)]
+pub struct StructureTensor {
+    pub sxx: Image<Gray<f32>>,
+    pub syy: Image<Gray<f32>>,
+    pub sxy: Image<Gray<f32>>,
+}
+
+impl StructureTensor {
+    /// Structure tensor from Sobel gradients of `image`, with the same size as the input.
+    ///
+    /// The image is first blurred with a Gaussian of `derivative_sigma`, or used as is if it is
+    /// zero. The gradient products are then averaged with a Gaussian window of
+    /// `integration_sigma`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `derivative_sigma` is negative or `integration_sigma` is not positive.
+    pub fn new(
+        image: &ImageView<Gray<f32>>,
+        derivative_sigma: f32,
+        integration_sigma: f32,
+        border: Border<Gray<f32>>,
+    ) -> Self {
+        assert!(derivative_sigma >= 0.0, "Derivative sigma must be non-negative");
+        let (ix, iy) = if derivative_sigma > 0.0 {
+            sobel_border(&gaussian(image, derivative_sigma, border).view(), border)
+        } else {
+            sobel_border(image, border)
+        };
+        let window = SeparableKernel::gaussian(integration_sigma);
+        Self::from_gradients(&ix.view(), &iy.view(), &window, border)
+    }
+
+    /// Structure tensor from precomputed gradients, averaging their products with `window`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ix` and `iy` differ in size.
+    pub fn from_gradients(
+        ix: &ImageView<Gray<f32>>,
+        iy: &ImageView<Gray<f32>>,
+        window: &SeparableKernel,
+        border: Border<Gray<f32>>,
+    ) -> Self {
+        Self::from_gradients_pooled(ix, iy, window, border, &mut ImagePool::new())
+    }
+
+    /// Like [`StructureTensor::from_gradients`], but takes all buffers, including the
+    /// intermediate ones, from `pool`. Hand the tensor back with [`StructureTensor::recycle`]
+    /// once done with it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ix` and `iy` differ in size.
+    pub fn from_gradients_pooled(
+        ix: &ImageView<Gray<f32>>,
+        iy: &ImageView<Gray<f32>>,
+        window: &SeparableKernel,
+        border: Border<Gray<f32>>,
+        pool: &mut ImagePool<Gray<f32>>,
+    ) -> Self {
+        let (w, h) = (ix.width(), ix.height());
+        let mut take = || pool.take(w, h, Gray::new(0.0));
+        let (mut product, mut scratch) = (take(), take());
+        let (mut sxx, mut syy, mut sxy) = (take(), take(), take());
+
+        let mut integrate = |a: &ImageView<Gray<f32>>,
+                             b: &ImageView<Gray<f32>>,
+                             dst: &mut Image<Gray<f32>>|
+         -> Result<(), ImageError> {
+            mul_into(a, b, &mut product.view_mut())?;
+            let (src, mut scratch) = (product.view(), scratch.view_mut());
+            apply_separable_into(&src, window, border, &mut scratch, &mut dst.view_mut())
+        };
+        integrate(ix, ix, &mut sxx)
+            .and_then(|_| integrate(iy, iy, &mut syy))
+            .and_then(|_| integrate(ix, iy, &mut sxy))
+            .unwrap_or_else(|e| panic!("{e}"));
+
+        pool.put(product);
+        pool.put(scratch);
+        Self { sxx, syy, sxy }
+    }
+
+    /// Return the component buffers to `pool`.
+    pub fn recycle(self, pool: &mut ImagePool<Gray<f32>>) {
+        pool.put(self.sxx);
+        pool.put(self.syy);
+        pool.put(self.sxy);
+    }
+
+    #[inline]
+    pub fn width(&self) -> usize { self.sxx.width() }
+
+    #[inline]
+    pub fn height(&self) -> usize { self.sxx.height() }
+
+    /// Harris corner response `det - k * trace²`.
+    pub fn harris(&self, k: f32) -> Image<Gray<f32>> {
+        let mut dst = Image::filled(self.width(), self.height(), Gray::new(0.0));
+        self.harris_into(k, &mut dst.view_mut()).unwrap_or_else(|e| panic!("{e}"));
+        dst
+    }
+
+    /// Like [`StructureTensor::harris`], but writes into `dst`, which must have the size of the
+    /// tensor.
+    pub fn harris_into(&self, k: f32, dst: &mut ImageViewMut<Gray<f32>>) -> Result<(), ImageError> {
+        check_size(dst, self.width(), self.height())?;
+        fill_with(dst, |x, y| {
+            let (xx, yy, xy) = self.at(x, y);
+            let trace = xx + yy;
+            Gray::new(xx * yy - xy * xy - k * trace * trace)
+        });
+        Ok(())
+    }
+
+    /// Smaller eigenvalue of the tensor, the Shi-Tomasi corner response.
+    pub fn min_eigenvalue(&self) -> Image<Gray<f32>> {
+        let mut dst = Image::filled(self.width(), self.height(), Gray::new(0.0));
+        self.min_eigenvalue_into(&mut dst.view_mut()).unwrap_or_else(|e| panic!("{e}"));
+        dst
+    }
+
+    /// Like [`StructureTensor::min_eigenvalue`], but writes into `dst`, which must have the size
+    /// of the tensor.
+    pub fn min_eigenvalue_into(&self, dst: &mut ImageViewMut<Gray<f32>>) -> Result<(), ImageError> {
+        check_size(dst, self.width(), self.height())?;
+        fill_with(dst, |x, y| {
+            let (xx, yy, xy) = self.at(x, y);
+            Gray::new(0.5 * (xx + yy) - (0.5 * (xx - yy)).hypot(xy))
+        });
+        Ok(())
+    }
+
+    #[inline]
+    fn at(&self, x: usize, y: usize) -> (f32, f32, f32) {
+        (self.sxx.get(x, y).value, self.syy.get(x, y).value, self.sxy.get(x, y).value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tensor_of_vertical_edge() {
+        // Brightness increasing to the right: only Sxx is non-zero
+        let data: Vec<Gray<f32>> = (0..144).map(|v| Gray::new((v % 12) as f32)).collect();
+        let img = Image::new(12, 12, 12, data);
+        let tensor = StructureTensor::new(&img.view(), 0.0, 1.0, Border::Replicate);
+
+        assert!((tensor.sxx.get(6, 6).value - 64.0).abs() < 1e-3);
+        assert_eq!(tensor.syy.get(6, 6).value, 0.0);
+        assert_eq!(tensor.sxy.get(6, 6).value, 0.0);
+        // An edge has a single dominant direction
+        assert!(tensor.min_eigenvalue().get(6, 6).value.abs() < 1e-3);
+        assert!(tensor.harris(0.04).get(6, 6).value < 0.0);
+    }
+
+    #[test]
+    fn pooled_tensor_matches_and_recycles() {
+        let data: Vec<Gray<f32>> = (0..49).map(|v| Gray::new(((v * 5) % 11) as f32)).collect();
+        let img = Image::new(7, 7, 7, data);
+        let (ix, iy) = sobel_border(&img.view(), Border::Reflect101);
+        let window = SeparableKernel::gaussian(1.0);
+
+        let mut pool = ImagePool::new();
+        let pooled = StructureTensor::from_gradients_pooled(
+            &ix.view(),
+            &iy.view(),
+            &window,
+            Border::Reflect101,
+            &mut pool,
+        );
+        assert_eq!(pool.len(), 2);
+        let plain =
+            StructureTensor::from_gradients(&ix.view(), &iy.view(), &window, Border::Reflect101);
+        assert_eq!(pooled.sxy.data(), plain.sxy.data());
+
+        pooled.recycle(&mut pool);
+        assert_eq!(pool.len(), 5);
+    }
+}
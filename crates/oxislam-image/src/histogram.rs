@@ -0,0 +1,272 @@
+//! Intensity histograms and histogram-based contrast enhancement.
+
+use crate::image::{Image, ImageView, map};
+use crate::parallel::par_row_collect;
+use crate::pixel::Gray;
+
+/// Pixel counts over equally sized bins spanning `[min, max)`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram {
+    counts: Vec<u64>,
+    min: f32,
+    max: f32,
+}
+
+impl Histogram {
+    /// Empty histogram with `bins` bins over `[min, max)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bins` is zero or the range is empty.
+    pub fn new(bins: usize, min: f32, max: f32) -> Self {
+        assert!(bins > 0, "Histogram needs at least one bin");
+        assert!(min < max, "Histogram range must be non-empty");
+        Self { counts: vec![0; bins], min, max }
+    }
+
+    /// Bin index of `value`. Values outside the range fall into the first or last bin.
+    #[inline]
+    pub fn bin(&self, value: f32) -> usize {
+        let t = (value - self.min) / (self.max - self.min);
+        ((t * self.counts.len() as f32) as usize).min(self.counts.len() - 1)
+    }
+
+    /// Count `value`; NaN is ignored.
+    #[inline]
+    pub fn add(&mut self, value: f32) {
+        if !value.is_nan() {
+            let bin = self.bin(value);
+            self.counts[bin] += 1;
+        }
+    }
+
+    #[inline]
+    pub fn counts(&self) -> &[u64] { &self.counts }
+
+    #[inline]
+    pub fn bins(&self) -> usize { self.counts.len() }
+
+    #[inline]
+    pub fn range(&self) -> (f32, f32) { (self.min, self.max) }
+
+    /// Total number of counted values.
+    pub fn total(&self) -> u64 { self.counts.iter().sum() }
+
+    /// Cumulative counts, where entry `i` counts the values in bins `0..=i`.
+    pub fn cumulative(&self) -> Vec<u64> {
+        self.counts
+            .iter()
+            .scan(0, |sum, &n| {
+                *sum += n;
+                Some(*sum)
+            })
+            .collect()
+    }
+}
+
+/// Histogram with one bin per 8-bit value.
+pub fn histogram_u8(image: &ImageView<Gray<u8>>) -> Histogram {
+    let mut hist = Histogram::new(256, 0.0, 256.0);
+    for p in image.pixels() {
+        hist.counts[p.value as usize] += 1;
+    }
+    hist
+}
+
+/// Histogram with `bins` bins over `[min, max)`; see [`Histogram::add`] for out-of-range
+/// values.
+pub fn histogram_f32(image: &ImageView<Gray<f32>>, bins: usize, min: f32, max: f32) -> Histogram {
+    let mut hist = Histogram::new(bins, min, max);
+    for p in image.pixels() {
+        hist.add(p.value);
+    }
+    hist
+}
+
+/// Lookup table mapping each 8-bit value to its rank in `counts`, scaled to `0..=255`.
+fn equalization_lut(counts: &[u64]) -> [u8; 256] {
+    let mut lut = [0; 256];
+    let total: u64 = counts.iter().sum();
+    let mut cdf = 0;
+    for (v, &n) in counts.iter().enumerate() {
+        cdf += n;
+        lut[v] = (cdf as f64 * 255.0 / total as f64).round() as u8;
+    }
+    lut
+}
+
+/// Global histogram equalization, spreading the values so their histogram becomes roughly
+/// flat.
+///
+/// The darkest value present maps to 0 and the brightest to 255. Images with a single value
+/// are returned unchanged.
+pub fn equalize(image: &ImageView<Gray<u8>>) -> Image<Gray<u8>> {
+    let hist = histogram_u8(image);
+    let cdf = hist.cumulative();
+    let total = hist.total();
+    let first = cdf.iter().copied().find(|&c| c > 0).unwrap_or(0);
+    if total == first {
+        return map(image, |&p| p);
+    }
+
+    let scale = 255.0 / (total - first) as f64;
+    let lut: Vec<u8> =
+        cdf.iter().map(|&c| (c.saturating_sub(first) as f64 * scale).round() as u8).collect();
+    map(image, |p| Gray::new(lut[p.value as usize]))
+}
+
+/// Start of tile `i` when splitting `len` pixels into `tiles` near-equal tiles.
+#[inline]
+fn tile_start(i: usize, len: usize, tiles: usize) -> usize { i * len / tiles }
+
+/// Tiles `(i, i + 1)` whose centers enclose `pos`, and the weight of tile `i + 1`.
+fn interpolation_cell(pos: usize, len: usize, tiles: usize) -> (usize, usize, f32) {
+    let center =
+        |i: usize| (tile_start(i, len, tiles) + tile_start(i + 1, len, tiles)) as f32 / 2.0 - 0.5;
+    let pos = pos as f32;
+    if pos <= center(0) {
+        return (0, 0, 0.0);
+    }
+    if pos >= center(tiles - 1) {
+        return (tiles - 1, tiles - 1, 0.0);
+    }
+    let i = (0..tiles - 1).find(|&i| pos < center(i + 1)).unwrap();
+    (i, i + 1, (pos - center(i)) / (center(i + 1) - center(i)))
+}
+
+/// Contrast-limited adaptive histogram equalization (CLAHE).
+///
+/// The image is split into a `tiles_x`x`tiles_y` grid and each tile is equalized on its own,
+/// with every pixel blending the mappings of the four nearest tiles to avoid visible seams.
+/// Before equalizing, histogram bins are clipped at `clip_limit` times the average bin count and
+/// the excess is spread over all bins, which bounds how strongly noise in flat regions is
+/// amplified. A limit of 1 leaves the image nearly unchanged, while larger limits approach
+/// unconstrained adaptive equalization.
+///
+/// # Panics
+///
+/// Panics if a tile count is zero or exceeds the image size, or `clip_limit` is below 1.
+pub fn clahe(
+    image: &ImageView<Gray<u8>>,
+    tiles_x: usize,
+    tiles_y: usize,
+    clip_limit: f32,
+) -> Image<Gray<u8>> {
+    let (w, h) = (image.width(), image.height());
+    assert!((1..=w).contains(&tiles_x) && (1..=h).contains(&tiles_y), "Invalid tile grid");
+    assert!(clip_limit >= 1.0, "Clip limit must be at least 1");
+
+    let luts: Vec<[u8; 256]> = (0..tiles_y)
+        .flat_map(|ty| (0..tiles_x).map(move |tx| (tx, ty)))
+        .map(|(tx, ty)| {
+            let (x0, x1) = (tile_start(tx, w, tiles_x), tile_start(tx + 1, w, tiles_x));
+            let (y0, y1) = (tile_start(ty, h, tiles_y), tile_start(ty + 1, h, tiles_y));
+            // Count in units of 1/256 pixel, so the average bin holds exactly the tile area and
+            // the limit stays accurate for tiles with fewer than 256 pixels
+            let mut counts = [0u64; 256];
+            for y in y0..y1 {
+                for x in x0..x1 {
+                    counts[image.get(x, y).value as usize] += 256;
+                }
+            }
+
+            let area = ((x1 - x0) * (y1 - y0)) as f32;
+            let limit = (clip_limit * area) as u64;
+            let mut excess = 0;
+            for n in &mut counts {
+                excess += n.saturating_sub(limit);
+                *n = (*n).min(limit);
+            }
+            let (share, remainder) = (excess / 256, (excess % 256) as usize);
+            counts.iter_mut().for_each(|n| *n += share);
+            if let Some(step) = 256usize.checked_div(remainder) {
+                for n in counts.iter_mut().step_by(step).take(remainder) {
+                    *n += 1;
+                }
+            }
+            equalization_lut(&counts)
+        })
+        .collect();
+
+    let data = par_row_collect(w, h, |x, y| {
+        let (tx0, tx1, fx) = interpolation_cell(x, w, tiles_x);
+        let (ty0, ty1, fy) = interpolation_cell(y, h, tiles_y);
+        let v = image.get(x, y).value as usize;
+        let at = |tx: usize, ty: usize| luts[ty * tiles_x + tx][v] as f32;
+
+        let top = at(tx0, ty0) * (1.0 - fx) + at(tx1, ty0) * fx;
+        let bottom = at(tx0, ty1) * (1.0 - fx) + at(tx1, ty1) * fx;
+        Gray::new((top * (1.0 - fy) + bottom * fy).round() as u8)
+    });
+    Image::new(w, h, w, data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn histogram_counts() {
+        let data = [0u8, 0, 7, 255, 7, 7].into_iter().map(Gray::new).collect();
+        let hist = histogram_u8(&Image::new(3, 2, 3, data).view());
+        assert_eq!((hist.counts()[0], hist.counts()[7], hist.counts()[255]), (2, 3, 1));
+        assert_eq!(hist.cumulative()[7], 5);
+
+        let data = [0.0, 0.2, 0.5, 0.99, 1.0, -3.0, f32::NAN, 0.3].into_iter().map(Gray::new);
+        let hist = histogram_f32(&Image::new(4, 2, 4, data.collect()).view(), 4, 0.0, 1.0);
+        assert_eq!(hist.counts(), [3, 1, 1, 2]);
+        assert_eq!(hist.total(), 7);
+    }
+
+    #[test]
+    fn equalize_stretches_narrow_histogram() {
+        let data = (0..64).map(|i| Gray::new(100 + (i % 8) as u8)).collect();
+        let img = Image::new(8, 8, 8, data);
+
+        let out = equalize(&img.view());
+        assert_eq!(out.get(0, 0).value, 0);
+        assert_eq!(out.get(7, 0).value, 255);
+        assert!(out.get(3, 0).value < out.get(4, 0).value);
+
+        let flat = Image::filled(3, 3, Gray::new(42u8));
+        assert_eq!(equalize(&flat.view()).data(), flat.data());
+    }
+
+    #[test]
+    fn clahe_enhances_each_region() {
+        // Left half dim, right half bright, both with little contrast
+        let data = (0..32 * 32)
+            .map(|i| {
+                let (x, y) = (i % 32, i / 32);
+                Gray::new(if x < 16 { 20 } else { 200 } + ((x + y) % 8) as u8)
+            })
+            .collect();
+        let img = Image::new(32, 32, 32, data);
+
+        let spread = |out: &Image<Gray<u8>>, x0: usize| {
+            let values: Vec<u8> = (0..16).map(|x| out.get(x0 + x, 8).value).collect();
+            values.iter().max().unwrap() - values.iter().min().unwrap()
+        };
+        let strong = clahe(&img.view(), 2, 2, 40.0);
+        assert!(spread(&strong, 0) > 150 && spread(&strong, 16) > 150);
+        // A lower clip limit caps the gain
+        let limited = clahe(&img.view(), 2, 2, 4.0);
+        assert!(spread(&limited, 0) < spread(&strong, 0) && spread(&limited, 0) > 7);
+
+        // With a limit of 1, every tile histogram is clipped flat and the mapping is near identity
+        let ramp = (0..32 * 32).map(|i| Gray::new(((i % 16) * 16 + (i / 32) % 16) as u8)).collect();
+        let ramp = Image::new(32, 32, 32, ramp);
+        let out = clahe(&ramp.view(), 2, 2, 1.0);
+        for (a, b) in out.view().pixels().zip(ramp.view().pixels()) {
+            assert!(a.value.abs_diff(b.value) <= 1);
+        }
+
+        // Also for tiles smaller than the 256 histogram bins
+        let small = (0..64).map(|i| Gray::new((i * 4) as u8)).collect();
+        let small = Image::new(8, 8, 8, small);
+        let out = clahe(&small.view(), 2, 2, 1.0);
+        for (a, b) in out.view().pixels().zip(small.view().pixels()) {
+            assert!(a.value.abs_diff(b.value) <= 8);
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use std::mem::{align_of, size_of};
+
+use super::types::{ImageView, ImageViewMut, RawChannels};
+use crate::error::ImageError;
+
+/// Pixel types that can be reinterpreted from raw bytes.
+///
+/// # Safety
+///
+/// Implementors must have no padding and accept every bit pattern as a valid value.
+pub unsafe trait BytePixel: Copy + 'static {}
+
+// SAFETY: RawChannels pixels consist of padding-free raw channel values.
+unsafe impl<P: RawChannels> BytePixel for P {}
+
+/// Validate a byte buffer layout and return the stride and length in pixels.
+fn pixel_layout<P: BytePixel>(
+    bytes: &[u8],
+    stride_bytes: usize,
+) -> Result<(usize, usize), ImageError> {
+    let pixel_size = size_of::<P>();
+    let align = align_of::<P>();
+
+    if bytes.as_ptr().align_offset(align) != 0 {
+        return Err(ImageError::Misaligned { align });
+    }
+    if !stride_bytes.is_multiple_of(pixel_size) {
+        return Err(ImageError::StrideNotMultiple { stride_bytes, pixel_size });
+    }
+    Ok((stride_bytes / pixel_size, bytes.len() / pixel_size))
+}
+
+impl<'a, P: BytePixel> ImageView<'a, P> {
+    /// Borrow a byte buffer as an image without copying.
+    ///
+    /// `stride_bytes` is the distance between rows in bytes and must be a multiple of the pixel
+    /// size. Multi-byte channels are read in native byte order.
+    pub fn from_bytes(
+        bytes: &'a [u8],
+        width: usize,
+        height: usize,
+        stride_bytes: usize,
+    ) -> Result<Self, ImageError> {
+        let (stride, len) = pixel_layout::<P>(bytes, stride_bytes)?;
+
+        // SAFETY: the pointer is aligned for P, `len` pixels fit within `bytes`, and BytePixel
+        // guarantees any bit pattern is a valid P.
+        let data = unsafe { std::slice::from_raw_parts(bytes.as_ptr() as *const P, len) };
+        ImageView::try_new(data, width, height, stride)
+    }
+}
+
+impl<'a, P: BytePixel> ImageViewMut<'a, P> {
+    /// Mutably borrow a byte buffer as an image without copying.
+    ///
+    /// See [`ImageView::from_bytes`] for the layout requirements.
+    pub fn from_bytes_mut(
+        bytes: &'a mut [u8],
+        width: usize,
+        height: usize,
+        stride_bytes: usize,
+    ) -> Result<Self, ImageError> {
+        let (stride, len) = pixel_layout::<P>(bytes, stride_bytes)?;
+
+        // SAFETY: as in `ImageView::from_bytes`; the exclusive borrow of `bytes` is moved into
+        // the returned view.
+        let data = unsafe { std::slice::from_raw_parts_mut(bytes.as_mut_ptr() as *mut P, len) };
+        ImageViewMut::try_new(data, width, height, stride)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pixel::{Bgra, Gray, Rgb};
+
+    #[test]
+    fn gray_u16_from_bytes() {
+        let storage: [u16; 4] = [1, 256, 1000, 65535];
+        // SAFETY: u16 storage reinterpreted as bytes for the test input
+        let bytes = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 8) };
+
+        let view = ImageView::<Gray<u16>>::from_bytes(bytes, 2, 2, 4).unwrap();
+        assert_eq!(view.get(1, 0).value, 256);
+        assert_eq!(view.get(0, 1).value, 1000);
+        assert_eq!(view.get(1, 1).value, 65535);
+    }
+
+    #[test]
+    fn rgb_u8_with_padded_stride() {
+        // 2x2 RGB image with 3 bytes of padding per row
+        #[rustfmt::skip]
+        let bytes: [u8; 18] = [
+            1, 2, 3, 4, 5, 6, 0, 0, 0,
+            7, 8, 9, 10, 11, 12, 0, 0, 0,
+        ];
+        let view = ImageView::<Rgb<u8>>::from_bytes(&bytes, 2, 2, 8);
+        assert_eq!(
+            view.unwrap_err(),
+            ImageError::StrideNotMultiple { stride_bytes: 8, pixel_size: 3 }
+        );
+
+        let view = ImageView::<Rgb<u8>>::from_bytes(&bytes, 2, 2, 9).unwrap();
+        assert_eq!(view.stride(), 3);
+        assert_eq!(*view.get(0, 1), Rgb::new(7, 8, 9));
+        assert_eq!(*view.get(1, 1), Rgb::new(10, 11, 12));
+    }
+
+    #[test]
+    fn bgra_from_bytes() {
+        let bytes: [u8; 8] = [10, 20, 30, 255, 40, 50, 60, 128];
+        let view = ImageView::<Bgra<u8>>::from_bytes(&bytes, 2, 1, 8).unwrap();
+        assert_eq!(*view.get(1, 0), Bgra::new(40, 50, 60, 128));
+    }
+
+    #[test]
+    fn rejects_misaligned_and_short_buffers() {
+        let storage = [0f32; 5];
+        // SAFETY: f32 storage reinterpreted as bytes for the test input
+        let bytes = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 20) };
+
+        assert_eq!(
+            ImageView::<Gray<f32>>::from_bytes(&bytes[1..], 1, 1, 4).unwrap_err(),
+            ImageError::Misaligned { align: 4 }
+        );
+        assert_eq!(
+            ImageView::<Gray<f32>>::from_bytes(bytes, 2, 3, 8).unwrap_err(),
+            ImageError::BufferTooShort { len: 5, required: 6 }
+        );
+    }
+
+    #[test]
+    fn writes_through_mutable_view() {
+        let mut storage = [0f32; 6];
+        // SAFETY: f32 storage reinterpreted as bytes for the test input
+        let bytes = unsafe { std::slice::from_raw_parts_mut(storage.as_mut_ptr() as *mut u8, 24) };
+
+        let mut view = ImageViewMut::<Rgb<f32>>::from_bytes_mut(bytes, 2, 1, 24).unwrap();
+        *view.get_mut(1, 0) = Rgb::new(0.25, 0.5, 0.75);
+
+        assert_eq!(storage, [0.0, 0.0, 0.0, 0.25, 0.5, 0.75]);
+    }
+}
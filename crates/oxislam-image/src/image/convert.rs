@@ -1,112 +1,132 @@
-use super::types::{Image, ImageView};
-use crate::parallel::{MaybeSend, MaybeSync, par_row_collect};
-use crate::pixel::{Gray, Rgb};
+use super::ops::{map, map_into};
+use super::types::{Image, ImageView, ImageViewMut};
+use crate::error::ImageError;
+use crate::parallel::{MaybeSend, MaybeSync};
+use crate::pixel::{Bgr, Bgra, Channel, Gray, Pixel, Rgb, Rgba};
 
 pub trait ConvertTo<T> {
     fn to(&self) -> T;
 }
 
-// Gray<u8> -> Gray<f32>
-impl ConvertTo<Image<Gray<f32>>> for ImageView<'_, Gray<u8>> {
-    fn to(&self) -> Image<Gray<f32>> {
-        let (w, h, s, d) = (self.width(), self.height(), self.stride(), self.data());
-        Image::new(
-            w,
-            h,
-            w,
-            par_row_collect(w, h, |x, y| Gray::new(d[y * s + x].value as f32 / 255.0)),
-        )
+/// Per-pixel conversion backing [`ConvertTo`] and [`convert_into`].
+pub trait FromPixel<P> {
+    fn from_pixel(p: &P) -> Self;
+}
+
+impl<P: MaybeSync, Q: FromPixel<P> + MaybeSend> ConvertTo<Image<Q>> for ImageView<'_, P> {
+    fn to(&self) -> Image<Q> { map(self, Q::from_pixel) }
+}
+
+/// Convert into `dst`, which must have the same size as `src`.
+pub fn convert_into<P: MaybeSync, Q: FromPixel<P> + MaybeSend>(
+    src: &ImageView<P>,
+    dst: &mut ImageViewMut<Q>,
+) -> Result<(), ImageError> {
+    map_into(src, dst, Q::from_pixel)
+}
+
+// Same-layout channel conversions, including the identity
+impl<T: Channel, U: Channel> FromPixel<Gray<T>> for Gray<U> {
+    fn from_pixel(p: &Gray<T>) -> Self { p.convert_channels() }
+}
+
+impl<T: Channel, U: Channel> FromPixel<Rgb<T>> for Rgb<U> {
+    fn from_pixel(p: &Rgb<T>) -> Self { p.convert_channels() }
+}
+
+impl<T: Channel, U: Channel> FromPixel<Bgr<T>> for Bgr<U> {
+    fn from_pixel(p: &Bgr<T>) -> Self { p.convert_channels() }
+}
+
+impl<T: Channel, U: Channel> FromPixel<Rgba<T>> for Rgba<U> {
+    fn from_pixel(p: &Rgba<T>) -> Self { p.convert_channels() }
+}
+
+impl<T: Channel, U: Channel> FromPixel<Bgra<T>> for Bgra<U> {
+    fn from_pixel(p: &Bgra<T>) -> Self { p.convert_channels() }
+}
+
+// Rec. 601 luma from normalized channels
+#[inline]
+fn luma<T: Channel>(r: T, g: T, b: T) -> f32 {
+    r.to_normalized() * 0.299 + g.to_normalized() * 0.587 + b.to_normalized() * 0.114
+}
+
+// Color -> Gray (ignores alpha)
+impl<T: Channel, U: Channel> FromPixel<Rgb<T>> for Gray<U> {
+    fn from_pixel(p: &Rgb<T>) -> Self { Gray::new(U::from_normalized(luma(p.r, p.g, p.b))) }
+}
+
+impl<T: Channel, U: Channel> FromPixel<Bgr<T>> for Gray<U> {
+    fn from_pixel(p: &Bgr<T>) -> Self { Gray::new(U::from_normalized(luma(p.r, p.g, p.b))) }
+}
+
+impl<T: Channel, U: Channel> FromPixel<Rgba<T>> for Gray<U> {
+    fn from_pixel(p: &Rgba<T>) -> Self { Gray::new(U::from_normalized(luma(p.r, p.g, p.b))) }
+}
+
+impl<T: Channel, U: Channel> FromPixel<Bgra<T>> for Gray<U> {
+    fn from_pixel(p: &Bgra<T>) -> Self { Gray::new(U::from_normalized(luma(p.r, p.g, p.b))) }
+}
+
+// Layout conversions convert channels first, then reorder
+impl<T: Channel, U: Channel> FromPixel<Bgr<T>> for Rgb<U> {
+    fn from_pixel(p: &Bgr<T>) -> Self {
+        let p = p.convert_channels::<U>();
+        Rgb::new(p.r, p.g, p.b)
     }
 }
 
-// Gray<f32> -> Gray<u8>
-impl ConvertTo<Image<Gray<u8>>> for ImageView<'_, Gray<f32>> {
-    fn to(&self) -> Image<Gray<u8>> {
-        let (w, h, s, d) = (self.width(), self.height(), self.stride(), self.data());
-        Image::new(
-            w,
-            h,
-            w,
-            par_row_collect(w, h, |x, y| {
-                Gray::new((d[y * s + x].value * 255.0).clamp(0.0, 255.0) as u8)
-            }),
-        )
+impl<T: Channel, U: Channel> FromPixel<Rgb<T>> for Bgr<U> {
+    fn from_pixel(p: &Rgb<T>) -> Self {
+        let p = p.convert_channels::<U>();
+        Bgr::new(p.b, p.g, p.r)
     }
 }
 
-// Rgb<u8> -> Rgb<f32>
-impl ConvertTo<Image<Rgb<f32>>> for ImageView<'_, Rgb<u8>> {
-    fn to(&self) -> Image<Rgb<f32>> {
-        let (w, h, s, d) = (self.width(), self.height(), self.stride(), self.data());
-        Image::new(
-            w,
-            h,
-            w,
-            par_row_collect(w, h, |x, y| {
-                let px = &d[y * s + x];
-                Rgb::new(px.r as f32 / 255.0, px.g as f32 / 255.0, px.b as f32 / 255.0)
-            }),
-        )
+// Rgba -> Rgb (drops alpha)
+impl<T: Channel, U: Channel> FromPixel<Rgba<T>> for Rgb<U> {
+    fn from_pixel(p: &Rgba<T>) -> Self {
+        let p = p.convert_channels::<U>();
+        Rgb::new(p.r, p.g, p.b)
     }
 }
 
-// Rgb<f32> -> Rgb<u8>
-impl ConvertTo<Image<Rgb<u8>>> for ImageView<'_, Rgb<f32>> {
-    fn to(&self) -> Image<Rgb<u8>> {
-        let (w, h, s, d) = (self.width(), self.height(), self.stride(), self.data());
-        Image::new(
-            w,
-            h,
-            w,
-            par_row_collect(w, h, |x, y| {
-                let px = &d[y * s + x];
-                Rgb::new(
-                    (px.r * 255.0).clamp(0.0, 255.0) as u8,
-                    (px.g * 255.0).clamp(0.0, 255.0) as u8,
-                    (px.b * 255.0).clamp(0.0, 255.0) as u8,
-                )
-            }),
-        )
+// Bgra -> Rgb (drops alpha)
+impl<T: Channel, U: Channel> FromPixel<Bgra<T>> for Rgb<U> {
+    fn from_pixel(p: &Bgra<T>) -> Self {
+        let p = p.convert_channels::<U>();
+        Rgb::new(p.r, p.g, p.b)
     }
 }
 
-// Rgb<u8> -> Gray<f32> (direct single-pass)
-impl ConvertTo<Image<Gray<f32>>> for ImageView<'_, Rgb<u8>> {
-    fn to(&self) -> Image<Gray<f32>> {
-        let (w, h, s, d) = (self.width(), self.height(), self.stride(), self.data());
-        Image::new(
-            w,
-            h,
-            w,
-            par_row_collect(w, h, |x, y| {
-                let px = &d[y * s + x];
-                Gray::new((px.r as f32 * 0.299 + px.g as f32 * 0.587 + px.b as f32 * 0.114) / 255.0)
-            }),
-        )
+// Bgra -> Bgr (drops alpha)
+impl<T: Channel, U: Channel> FromPixel<Bgra<T>> for Bgr<U> {
+    fn from_pixel(p: &Bgra<T>) -> Self {
+        let p = p.convert_channels::<U>();
+        Bgr::new(p.b, p.g, p.r)
     }
 }
 
-// Rgb<f32> -> Gray<f32>
-impl ConvertTo<Image<Gray<f32>>> for ImageView<'_, Rgb<f32>> {
-    fn to(&self) -> Image<Gray<f32>> {
-        let (w, h, s, d) = (self.width(), self.height(), self.stride(), self.data());
-        Image::new(
-            w,
-            h,
-            w,
-            par_row_collect(w, h, |x, y| {
-                let px = &d[y * s + x];
-                Gray::new(px.r * 0.299 + px.g * 0.587 + px.b * 0.114)
-            }),
-        )
+impl<T: Channel, U: Channel> FromPixel<Bgra<T>> for Rgba<U> {
+    fn from_pixel(p: &Bgra<T>) -> Self {
+        let p = p.convert_channels::<U>();
+        Rgba::new(p.r, p.g, p.b, p.a)
     }
 }
 
-// Identity (clone with compact output)
-impl<P: Clone + MaybeSend + MaybeSync> ConvertTo<Image<P>> for ImageView<'_, P> {
-    fn to(&self) -> Image<P> {
-        let (w, h, s, d) = (self.width(), self.height(), self.stride(), self.data());
-        Image::new(w, h, w, par_row_collect(w, h, |x, y| d[y * s + x].clone()))
+impl<T: Channel, U: Channel> FromPixel<Rgba<T>> for Bgra<U> {
+    fn from_pixel(p: &Rgba<T>) -> Self {
+        let p = p.convert_channels::<U>();
+        Bgra::new(p.b, p.g, p.r, p.a)
+    }
+}
+
+// Rgb -> Rgba (opaque)
+impl<T: Channel, U: Channel> FromPixel<Rgb<T>> for Rgba<U> {
+    fn from_pixel(p: &Rgb<T>) -> Self {
+        let p = p.convert_channels::<U>();
+        Rgba::new(p.r, p.g, p.b, U::MAX_VALUE)
     }
 }
 
@@ -131,4 +151,83 @@ mod tests {
         let gray_b: Image<Gray<f32>> = blue.view().to();
         assert!((gray_b.get(0, 0).value - 0.114).abs() < 1e-6);
     }
+
+    #[test]
+    fn bgr_rgb_channel_order() {
+        let bgr = Image::new(1, 1, 1, vec![Bgr::new(255u8, 0u8, 0u8)]);
+        let rgb: Image<Rgb<u8>> = bgr.view().to();
+        assert_eq!(*rgb.get(0, 0), Rgb::new(0, 0, 255));
+
+        // Pure blue in BGR order has the blue luminance weight
+        let gray: Image<Gray<f32>> = bgr.view().to();
+        assert!((gray.get(0, 0).value - 0.114).abs() < 1e-6);
+
+        let bgra = Image::new(1, 1, 1, vec![Bgra::new(1u8, 2u8, 3u8, 4u8)]);
+        let rgba: Image<Rgba<u8>> = bgra.view().to();
+        assert_eq!(*rgba.get(0, 0), Rgba::new(3, 2, 1, 4));
+    }
+
+    #[test]
+    fn u16_depth_roundtrip() {
+        let depth = Image::new(3, 1, 3, vec![Gray::new(0u16), Gray::new(32768), Gray::new(65535)]);
+        let f: Image<Gray<f32>> = depth.view().to();
+        assert_eq!(f.get(0, 0).value, 0.0);
+        assert_eq!(f.get(2, 0).value, 1.0);
+
+        let back: Image<Gray<u16>> = f.view().to();
+        assert_eq!(back.as_raw(), depth.as_raw());
+
+        let u8s: Image<Gray<u8>> = depth.view().to();
+        assert_eq!(u8s.as_raw(), &[0, 128, 255]);
+    }
+
+    #[test]
+    fn channel_conversions_round() {
+        let f = Image::new(3, 1, 3, vec![Gray::new(0.999f32), Gray::new(0.5), Gray::new(-0.2)]);
+        let u8s: Image<Gray<u8>> = f.view().to();
+        assert_eq!(u8s.as_raw(), &[255, 128, 0]);
+
+        let rgb = Image::new(1, 1, 1, vec![Rgb::new(0.999f32, 0.5, 1.2)]);
+        let rgb_u8: Image<Rgb<u8>> = rgb.view().to();
+        assert_eq!(*rgb_u8.get(0, 0), Rgb::new(255, 128, 255));
+
+        // Identity conversions are exact
+        let precise = Image::new(1, 1, 1, vec![Gray::new(0.1f64)]);
+        let same: Image<Gray<f64>> = precise.view().to();
+        assert_eq!(same.get(0, 0).value, 0.1);
+    }
+
+    #[test]
+    fn mixed_channel_and_layout() {
+        let bgr = Image::new(1, 1, 1, vec![Bgr::new(0u8, 0, 255)]);
+        let gray: Image<Gray<u8>> = bgr.view().to();
+        assert_eq!(gray.get(0, 0).value, 76); // 0.299 * 255, rounded
+
+        let rgb: Image<Rgb<u16>> = bgr.view().to();
+        assert_eq!(*rgb.get(0, 0), Rgb::new(65535, 0, 0));
+        let rgba: Image<Rgba<u16>> = rgb.view().to();
+        assert_eq!(*rgba.get(0, 0), Rgba::new(65535, 0, 0, 65535));
+        let bgra: Image<Bgra<u8>> = rgba.view().to();
+        let back: Image<Bgr<u8>> = bgra.view().to();
+        assert_eq!(back.as_raw(), bgr.as_raw());
+        let gray: Image<Gray<u8>> = bgra.view().to();
+        assert_eq!(gray.get(0, 0).value, 76);
+
+        let depth = Image::new(2, 1, 2, vec![Gray::new(0.5f64), Gray::new(1.0)]);
+        let u16s: Image<Gray<u16>> = depth.view().to();
+        assert_eq!(u16s.as_raw(), &[32768, 65535]);
+        let u8s: Image<Gray<u8>> = depth.view().to();
+        assert_eq!(u8s.as_raw(), &[128, 255]);
+        let f64s: Image<Gray<f64>> = u8s.view().to();
+        assert_eq!(f64s.get(1, 0).value, 1.0);
+    }
+
+    #[test]
+    fn convert_into_subview() {
+        let rgb = Image::new(2, 1, 2, vec![Rgb::new(255u8, 0, 0), Rgb::new(0, 0, 255)]);
+        let mut bgr = Image::filled(3, 1, Bgr::new(9u8, 9, 9));
+
+        convert_into(&rgb.view(), &mut bgr.subview_mut(1, 0, 2, 1).unwrap()).unwrap();
+        assert_eq!(bgr.data(), &[Bgr::new(9, 9, 9), Bgr::new(0, 0, 255), Bgr::new(255, 0, 0)]);
+    }
 }
@@ -1,7 +1,22 @@
+mod bytes;
 mod convert;
 mod ops;
+mod remap;
+mod resize;
+mod sample;
+mod tiles;
 mod types;
+mod warp;
 
-pub use convert::ConvertTo;
-pub use ops::{map, map2};
-pub use types::{Image, ImageView, ImageViewMut};
+pub use bytes::BytePixel;
+pub use convert::{ConvertTo, FromPixel, convert_into};
+pub use ops::{
+    add_into, map, map_inplace, map_into, map2, map2_into, mul_into, sub_into, try_map2,
+};
+pub(crate) use ops::{check_size, fill_with};
+pub use remap::{RemapTable, remap};
+pub use resize::resize;
+pub use sample::{Interpolate, Interpolation};
+pub use tiles::{TileMut, TilesMut};
+pub use types::{Image, ImageView, ImageViewMut, RawChannels, RawPixel};
+pub use warp::{warp_affine, warp_perspective};
@@ -1,8 +1,9 @@
 use std::ops::{Add, Mul, Sub};
 
-use super::{Image, ImageView};
-use crate::parallel::{MaybeSend, MaybeSync, par_row_collect};
-use crate::pixel::Gray;
+use super::{Image, ImageView, ImageViewMut};
+use crate::error::ImageError;
+use crate::parallel::{MaybeSend, MaybeSync, par_row_collect, par_rows_mut};
+use crate::pixel::{Channel, Pixel};
 
 pub fn map<P: MaybeSync, Q: MaybeSend, F>(img: &ImageView<P>, f: F) -> Image<Q>
 where
@@ -15,6 +16,9 @@ where
     Image::new(w, h, w, data)
 }
 
+/// # Panics
+///
+/// Panics if the images differ in size; see [`try_map2`].
 pub fn map2<P: MaybeSync, Q: MaybeSync, R: MaybeSend, F>(
     a: &ImageView<P>,
     b: &ImageView<Q>,
@@ -23,36 +27,203 @@ pub fn map2<P: MaybeSync, Q: MaybeSync, R: MaybeSend, F>(
 where
     F: Fn(&P, &Q) -> R + MaybeSync,
 {
-    assert_eq!(a.width(), b.width());
-    assert_eq!(a.height(), b.height());
+    try_map2(a, b, f).unwrap_or_else(|e| panic!("{e}"))
+}
+
+pub fn try_map2<P: MaybeSync, Q: MaybeSync, R: MaybeSend, F>(
+    a: &ImageView<P>,
+    b: &ImageView<Q>,
+    f: F,
+) -> Result<Image<R>, ImageError>
+where
+    F: Fn(&P, &Q) -> R + MaybeSync,
+{
+    if (a.width(), a.height()) != (b.width(), b.height()) {
+        return Err(ImageError::SizeMismatch {
+            expected: (a.width(), a.height()),
+            actual: (b.width(), b.height()),
+        });
+    }
 
     let w = a.width();
     let h = a.height();
     let f = &f;
     let data = par_row_collect(w, h, |x, y| f(a.get(x, y), b.get(x, y)));
-    Image::new(w, h, w, data)
+    Ok(Image::new(w, h, w, data))
+}
+
+/// Check that `dst` is `width`x`height`.
+pub(crate) fn check_size<P>(
+    dst: &ImageViewMut<P>,
+    width: usize,
+    height: usize,
+) -> Result<(), ImageError> {
+    if (dst.width(), dst.height()) != (width, height) {
+        return Err(ImageError::SizeMismatch {
+            expected: (width, height),
+            actual: (dst.width(), dst.height()),
+        });
+    }
+    Ok(())
+}
+
+/// Overwrite every pixel of `dst` with `f(x, y)`, parallelizing across rows when rayon is enabled.
+pub(crate) fn fill_with<P: MaybeSend, F>(dst: &mut ImageViewMut<P>, f: F)
+where
+    F: Fn(usize, usize) -> P + MaybeSync,
+{
+    let (w, h, s) = (dst.width(), dst.height(), dst.stride());
+    par_rows_mut(dst.data_mut(), w, h, s, |y, row| {
+        for (x, px) in row.iter_mut().enumerate() {
+            *px = f(x, y);
+        }
+    });
+}
+
+/// Like [`map`], but writes into `dst`, which must have the same size as `img`.
+pub fn map_into<P: MaybeSync, Q: MaybeSend, F>(
+    img: &ImageView<P>,
+    dst: &mut ImageViewMut<Q>,
+    f: F,
+) -> Result<(), ImageError>
+where
+    F: Fn(&P) -> Q + MaybeSync,
+{
+    check_size(dst, img.width(), img.height())?;
+    fill_with(dst, |x, y| f(img.get(x, y)));
+    Ok(())
+}
+
+/// Like [`map2`], but writes into `dst`. All three images must have the same size.
+pub fn map2_into<P: MaybeSync, Q: MaybeSync, R: MaybeSend, F>(
+    a: &ImageView<P>,
+    b: &ImageView<Q>,
+    dst: &mut ImageViewMut<R>,
+    f: F,
+) -> Result<(), ImageError>
+where
+    F: Fn(&P, &Q) -> R + MaybeSync,
+{
+    if (a.width(), a.height()) != (b.width(), b.height()) {
+        return Err(ImageError::SizeMismatch {
+            expected: (a.width(), a.height()),
+            actual: (b.width(), b.height()),
+        });
+    }
+    check_size(dst, a.width(), a.height())?;
+    fill_with(dst, |x, y| f(a.get(x, y), b.get(x, y)));
+    Ok(())
+}
+
+/// Replace every pixel with `f(pixel)`.
+pub fn map_inplace<P: MaybeSend, F>(img: &mut ImageViewMut<P>, f: F)
+where
+    F: Fn(&P) -> P + MaybeSync,
+{
+    let (w, h, s) = (img.width(), img.height(), img.stride());
+    par_rows_mut(img.data_mut(), w, h, s, |_, row| {
+        for px in row {
+            *px = f(px);
+        }
+    });
 }
 
-impl Mul for &Image<Gray<f32>> {
-    type Output = Image<Gray<f32>>;
+/// Per-channel saturating `a + b` into `dst`.
+pub fn add_into<P: Pixel>(
+    a: &ImageView<P>,
+    b: &ImageView<P>,
+    dst: &mut ImageViewMut<P>,
+) -> Result<(), ImageError> {
+    map2_into(a, b, dst, |a, b| a.zip_map(*b, Channel::saturating_add))
+}
+
+/// Per-channel saturating `a - b` into `dst`.
+pub fn sub_into<P: Pixel>(
+    a: &ImageView<P>,
+    b: &ImageView<P>,
+    dst: &mut ImageViewMut<P>,
+) -> Result<(), ImageError> {
+    map2_into(a, b, dst, |a, b| a.zip_map(*b, Channel::saturating_sub))
+}
+
+/// Per-channel saturating `a * b` into `dst`.
+pub fn mul_into<P: Pixel>(
+    a: &ImageView<P>,
+    b: &ImageView<P>,
+    dst: &mut ImageViewMut<P>,
+) -> Result<(), ImageError> {
+    map2_into(a, b, dst, |a, b| a.zip_map(*b, Channel::saturating_mul))
+}
+
+// Per-channel arithmetic, saturating for integer channel types.
+
+impl<P: Pixel> Mul for &Image<P> {
+    type Output = Image<P>;
 
     fn mul(self, rhs: Self) -> Self::Output {
-        map2(&self.view(), &rhs.view(), |a, b| Gray::new(a.value * b.value))
+        map2(&self.view(), &rhs.view(), |a, b| a.zip_map(*b, Channel::saturating_mul))
     }
 }
 
-impl Add for &Image<Gray<f32>> {
-    type Output = Image<Gray<f32>>;
+impl<P: Pixel> Add for &Image<P> {
+    type Output = Image<P>;
 
     fn add(self, rhs: Self) -> Self::Output {
-        map2(&self.view(), &rhs.view(), |a, b| Gray::new(a.value + b.value))
+        map2(&self.view(), &rhs.view(), |a, b| a.zip_map(*b, Channel::saturating_add))
     }
 }
 
-impl Sub for &Image<Gray<f32>> {
-    type Output = Image<Gray<f32>>;
+impl<P: Pixel> Sub for &Image<P> {
+    type Output = Image<P>;
 
     fn sub(self, rhs: Self) -> Self::Output {
-        map2(&self.view(), &rhs.view(), |a, b| Gray::new(a.value - b.value))
+        map2(&self.view(), &rhs.view(), |a, b| a.zip_map(*b, Channel::saturating_sub))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pixel::{Gray, Rgb};
+
+    #[test]
+    fn arithmetic_saturates_integer_channels() {
+        let a = Image::new(1, 1, 1, vec![Rgb::new(200u8, 10, 7)]);
+        let b = Image::new(1, 1, 1, vec![Rgb::new(100u8, 20, 3)]);
+
+        assert_eq!(*(&a + &b).get(0, 0), Rgb::new(255, 30, 10));
+        assert_eq!(*(&a - &b).get(0, 0), Rgb::new(100, 0, 4));
+        assert_eq!(*(&a * &b).get(0, 0), Rgb::new(255, 200, 21));
+    }
+
+    #[test]
+    fn arithmetic_float() {
+        let a = Image::new(2, 1, 2, vec![Gray::new(0.5f32), Gray::new(-1.0)]);
+        let b = Image::new(2, 1, 2, vec![Gray::new(0.25f32), Gray::new(3.0)]);
+
+        let values =
+            |img: Image<Gray<f32>>| img.view().pixels().map(|p| p.value).collect::<Vec<_>>();
+        assert_eq!(values(&a * &b), [0.125, -3.0]);
+        assert_eq!(values(&a + &b), [0.75, 2.0]);
+        assert_eq!(values(&a - &b), [0.25, -4.0]);
+    }
+
+    #[test]
+    fn write_into_preallocated_buffers() {
+        let a = Image::new(2, 1, 2, vec![Gray::new(200u8), Gray::new(5)]);
+        let b = Image::new(2, 1, 2, vec![Gray::new(100u8), Gray::new(10)]);
+
+        // Write into the left half of a padded buffer
+        let mut out = Image::filled(3, 2, Gray::new(1u8));
+        let mut dst = out.subview_mut(0, 1, 2, 1).unwrap();
+        add_into(&a.view(), &b.view(), &mut dst).unwrap();
+        map_inplace(&mut dst, |p| Gray::new(p.value / 5));
+        assert_eq!(out.as_raw(), &[1, 1, 1, 51, 3, 1]);
+
+        let mut small = Image::filled(1, 1, Gray::new(0u8));
+        assert_eq!(
+            sub_into(&a.view(), &b.view(), &mut small.view_mut()).unwrap_err(),
+            ImageError::SizeMismatch { expected: (2, 1), actual: (1, 1) }
+        );
     }
 }
@@ -0,0 +1,119 @@
+use super::sample::{Interpolate, Interpolation, sample_finite};
+use super::types::{Image, ImageView};
+use crate::border::Border;
+use crate::parallel::{MaybeSend, MaybeSync, par_row_collect};
+use crate::pixel::Gray;
+
+/// Resample an image through per-pixel lookup maps.
+///
+/// Output pixel `(x, y)` is sampled from the input at `(map_x[x, y], map_y[x, y])`, extrapolating
+/// according to `border`. Non-finite map entries, commonly used to mark invalid pixels in
+/// undistortion tables, yield the border value. The output has the size of the maps.
+pub fn remap<P>(
+    image: &ImageView<P>,
+    map_x: &ImageView<Gray<f32>>,
+    map_y: &ImageView<Gray<f32>>,
+    interpolation: Interpolation,
+    border: Border<P>,
+) -> Image<P>
+where
+    P: Interpolate + MaybeSend + MaybeSync,
+{
+    assert_eq!(map_x.width(), map_y.width());
+    assert_eq!(map_x.height(), map_y.height());
+
+    let w = map_x.width();
+    let h = map_x.height();
+    let data = par_row_collect(w, h, |x, y| {
+        sample_finite(image, map_x.get(x, y).value, map_y.get(x, y).value, interpolation, border)
+    });
+    Image::new(w, h, w, data)
+}
+
+/// Precomputed lookup maps for [`remap`], built once and applied to every frame.
+#[derive(Debug)]
+pub struct RemapTable {
+    map_x: Image<Gray<f32>>,
+    map_y: Image<Gray<f32>>,
+}
+
+impl RemapTable {
+    pub fn new(map_x: Image<Gray<f32>>, map_y: Image<Gray<f32>>) -> Self {
+        assert_eq!(map_x.width(), map_y.width());
+        assert_eq!(map_x.height(), map_y.height());
+        Self { map_x, map_y }
+    }
+
+    /// Build the maps by evaluating `f(x, y) -> (src_x, src_y)` for every output pixel.
+    pub fn from_fn<F>(width: usize, height: usize, f: F) -> Self
+    where
+        F: Fn(usize, usize) -> (f32, f32) + MaybeSync,
+    {
+        let coords = par_row_collect(width, height, f);
+        let map_x = coords.iter().map(|&(x, _)| Gray::new(x)).collect();
+        let map_y = coords.iter().map(|&(_, y)| Gray::new(y)).collect();
+        Self::new(Image::new(width, height, width, map_x), Image::new(width, height, width, map_y))
+    }
+
+    #[inline]
+    pub fn width(&self) -> usize { self.map_x.width() }
+
+    #[inline]
+    pub fn height(&self) -> usize { self.map_x.height() }
+
+    #[inline]
+    pub fn map_x(&self) -> ImageView<'_, Gray<f32>> { self.map_x.view() }
+
+    #[inline]
+    pub fn map_y(&self) -> ImageView<'_, Gray<f32>> { self.map_y.view() }
+
+    pub fn apply<P>(
+        &self,
+        image: &ImageView<P>,
+        interpolation: Interpolation,
+        border: Border<P>,
+    ) -> Image<P>
+    where
+        P: Interpolate + MaybeSend + MaybeSync,
+    {
+        remap(image, &self.map_x.view(), &self.map_y.view(), interpolation, border)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remap_flips_horizontally() {
+        let data: Vec<Gray<f32>> = (0..6).map(|v| Gray::new(v as f32)).collect();
+        let img = Image::new(3, 2, 3, data);
+
+        let table = RemapTable::from_fn(3, 2, |x, y| ((2 - x) as f32, y as f32));
+        let out = table.apply(&img.view(), Interpolation::Nearest, Border::Replicate);
+
+        let values: Vec<f32> = out.view().pixels().map(|p| p.value).collect();
+        assert_eq!(values, [2.0, 1.0, 0.0, 5.0, 4.0, 3.0]);
+    }
+
+    #[test]
+    fn remap_subpixel_and_border() {
+        let data: Vec<Gray<f32>> = (0..4).map(|v| Gray::new(v as f32)).collect();
+        let img = Image::new(4, 1, 4, data);
+
+        let map_x = Image::new(3, 1, 3, vec![Gray::new(1.5), Gray::new(10.0), Gray::new(f32::NAN)]);
+        let map_y = Image::filled(3, 1, Gray::new(0.0));
+        let out = remap(
+            &img.view(),
+            &map_x.view(),
+            &map_y.view(),
+            Interpolation::Bilinear,
+            Border::Constant(Gray::new(-1.0)),
+        );
+
+        assert_eq!(out.get(0, 0).value, 1.5);
+        assert_eq!(out.get(1, 0).value, -1.0);
+        // Invalid entries fall back to the border
+        assert_eq!(out.get(2, 0).value, -1.0);
+    }
+}
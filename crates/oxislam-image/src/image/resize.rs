@@ -0,0 +1,146 @@
+use super::sample::{Interpolate, Interpolation};
+use super::types::{Image, ImageView};
+use crate::border::Border;
+use crate::parallel::{MaybeSend, MaybeSync, par_row_collect};
+
+/// Source pixels overlapping `[start, start + scale)` and their coverage.
+fn area_weights(dst_len: usize, src_len: usize) -> Vec<Vec<(usize, f32)>> {
+    let scale = src_len as f32 / dst_len as f32;
+    (0..dst_len)
+        .map(|i| {
+            let start = i as f32 * scale;
+            let end = ((i + 1) as f32 * scale).min(src_len as f32);
+            let first = start.floor() as usize;
+            let last = (end.ceil() as usize).min(src_len);
+
+            let mut weights: Vec<(usize, f32)> = (first..last)
+                .map(|s| (s, end.min((s + 1) as f32) - start.max(s as f32)))
+                .filter(|&(_, w)| w > 0.0)
+                .collect();
+
+            let total: f32 = weights.iter().map(|(_, w)| w).sum();
+            for (_, w) in &mut weights {
+                *w /= total;
+            }
+            weights
+        })
+        .collect()
+}
+
+fn resize_area<P>(image: &ImageView<P>, width: usize, height: usize) -> Vec<P>
+where
+    P: Interpolate + MaybeSend + MaybeSync,
+{
+    let wx = area_weights(width, image.width());
+    let wy = area_weights(height, image.height());
+
+    par_row_collect(width, height, |x, y| {
+        let mut acc = P::zero();
+        for &(sy, wy) in &wy[y] {
+            for &(sx, wx) in &wx[x] {
+                acc = P::accumulate(acc, *image.get(sx, sy), wx * wy);
+            }
+        }
+        P::finish(acc)
+    })
+}
+
+/// Resize an image to `width`x`height`.
+///
+/// Pixel centers are aligned, so the image borders map onto each other exactly. Samples outside
+/// the source image replicate the edge pixels.
+pub fn resize<P>(
+    image: &ImageView<P>,
+    width: usize,
+    height: usize,
+    interpolation: Interpolation,
+) -> Image<P>
+where
+    P: Interpolate + MaybeSend + MaybeSync,
+{
+    assert!(width > 0 && height > 0, "Output size must be non-zero");
+
+    let data = match interpolation {
+        Interpolation::Area => resize_area(image, width, height),
+        _ => {
+            let sx = image.width() as f32 / width as f32;
+            let sy = image.height() as f32 / height as f32;
+            par_row_collect(width, height, |x, y| {
+                let fx = (x as f32 + 0.5) * sx - 0.5;
+                let fy = (y as f32 + 0.5) * sy - 0.5;
+                image.sample(fx, fy, interpolation, Border::Replicate)
+            })
+        }
+    };
+
+    Image::new(width, height, width, data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pixel::{Gray, Rgb};
+
+    fn gray_u8(width: usize, height: usize, values: &[u8]) -> Image<Gray<u8>> {
+        Image::new(width, height, width, values.iter().map(|&v| Gray::new(v)).collect())
+    }
+
+    #[test]
+    fn nearest_upsample_duplicates_pixels() {
+        let img = gray_u8(2, 2, &[1, 2, 3, 4]);
+        let out = resize(&img.view(), 4, 4, Interpolation::Nearest);
+
+        let values: Vec<u8> = out.view().pixels().map(|p| p.value).collect();
+        assert_eq!(values, [1, 1, 2, 2, 1, 1, 2, 2, 3, 3, 4, 4, 3, 3, 4, 4]);
+    }
+
+    #[test]
+    fn area_downsample_averages_blocks() {
+        #[rustfmt::skip]
+        let img = gray_u8(4, 2, &[
+            0, 10, 20, 40,
+            20, 30, 60, 80,
+        ]);
+        let out = resize(&img.view(), 2, 1, Interpolation::Area);
+
+        assert_eq!(out.get(0, 0).value, 15);
+        assert_eq!(out.get(1, 0).value, 50);
+    }
+
+    #[test]
+    fn area_fractional_coverage() {
+        let data: Vec<Gray<f32>> = [0.0, 3.0, 6.0].into_iter().map(Gray::new).collect();
+        let img = Image::new(3, 1, 3, data);
+        let out = resize(&img.view(), 2, 1, Interpolation::Area);
+
+        // Each output pixel covers 1.5 input pixels
+        assert!((out.get(0, 0).value - 1.0).abs() < 1e-5);
+        assert!((out.get(1, 0).value - 5.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn bilinear_downsample_by_two() {
+        let data: Vec<Gray<f32>> = (0..16).map(|v| Gray::new(v as f32)).collect();
+        let img = Image::new(4, 4, 4, data);
+        let out = resize(&img.view(), 2, 2, Interpolation::Bilinear);
+
+        // Each output pixel center sits between four input pixel centers
+        assert!((out.get(0, 0).value - 2.5).abs() < 1e-5);
+        assert!((out.get(1, 1).value - 12.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn constant_image_is_preserved() {
+        let img = Image::filled(7, 5, Rgb::new(10u8, 20u8, 30u8));
+        for interpolation in [
+            Interpolation::Nearest,
+            Interpolation::Bilinear,
+            Interpolation::Bicubic,
+            Interpolation::Area,
+        ] {
+            let out = resize(&img.view(), 3, 11, interpolation);
+            assert_eq!((out.width(), out.height()), (3, 11));
+            assert!(out.view().pixels().all(|p| *p == Rgb::new(10, 20, 30)));
+        }
+    }
+}
@@ -0,0 +1,234 @@
+use super::types::{Image, ImageView};
+use crate::border::Border;
+use crate::parallel::{MaybeSend, MaybeSync, par_row_collect};
+use crate::pixel::{Channel, Pixel};
+
+/// Pixels that can be blended by interpolation.
+///
+/// Blending accumulates in `f32` through [`Interpolate::Accum`], so integer pixel types are only
+/// rounded once when the result is finished. Implemented for every [`Pixel`].
+pub trait Interpolate: Copy {
+    type Accum: Copy;
+
+    fn zero() -> Self::Accum;
+
+    fn accumulate(acc: Self::Accum, pixel: Self, weight: f32) -> Self::Accum;
+
+    fn finish(acc: Self::Accum) -> Self;
+
+    /// Weighted sum of `pixels`.
+    #[inline]
+    fn weighted_sum<const N: usize>(pixels: [Self; N], weights: [f32; N]) -> Self {
+        let acc = pixels
+            .iter()
+            .zip(weights)
+            .fold(Self::zero(), |acc, (p, w)| Self::accumulate(acc, *p, w));
+        Self::finish(acc)
+    }
+}
+
+impl<P: Pixel> Interpolate for P {
+    type Accum = P::WithChannel<f32>;
+
+    #[inline]
+    fn zero() -> Self::Accum { Self::Accum::from_fn(|_| 0.0) }
+
+    #[inline]
+    fn accumulate(acc: Self::Accum, pixel: Self, weight: f32) -> Self::Accum {
+        acc.zip_map(pixel.map_channels(|c| c.to_f32()), |a, b| a + b * weight)
+    }
+
+    #[inline]
+    fn finish(acc: Self::Accum) -> Self { P::from_fn(|i| P::Channel::from_f32(acc.channel(i))) }
+}
+
+/// Interpolation method used when sampling between pixel centers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interpolation {
+    Nearest,
+    Bilinear,
+    Bicubic,
+    /// Box-averaged over the covered source area. Only meaningful when downsampling; point
+    /// sampling falls back to bilinear.
+    Area,
+}
+
+// Keys cubic convolution kernel with a = -0.5 (Catmull-Rom).
+#[inline]
+fn cubic_weights(t: f32) -> [f32; 4] {
+    const A: f32 = -0.5;
+    let near = |d: f32| ((A + 2.0) * d - (A + 3.0)) * d * d + 1.0;
+    let far = |d: f32| ((A * d - 5.0 * A) * d + 8.0 * A) * d - 4.0 * A;
+    [far(1.0 + t), near(t), near(1.0 - t), far(2.0 - t)]
+}
+
+impl<P: Interpolate> ImageView<'_, P> {
+    /// Sample at a sub-pixel position with the given interpolation method.
+    #[inline]
+    pub fn sample(&self, x: f32, y: f32, interpolation: Interpolation, border: Border<P>) -> P {
+        match interpolation {
+            Interpolation::Nearest => self.sample_nearest(x, y, border),
+            Interpolation::Bilinear | Interpolation::Area => self.sample_bilinear(x, y, border),
+            Interpolation::Bicubic => self.sample_bicubic(x, y, border),
+        }
+    }
+
+    /// Pixel whose center is nearest to a sub-pixel position.
+    #[inline]
+    pub fn sample_nearest(&self, x: f32, y: f32, border: Border<P>) -> P {
+        self.get_border((x + 0.5).floor() as isize, (y + 0.5).floor() as isize, border)
+    }
+
+    /// Bilinearly interpolate at a sub-pixel position, where integer coordinates hit pixel
+    /// centers. Neighbors outside the image are extrapolated according to `border`.
+    pub fn sample_bilinear(&self, x: f32, y: f32, border: Border<P>) -> P {
+        let (fx, fy) = (x.floor(), y.floor());
+        let (ax, ay) = (x - fx, y - fy);
+        let (x0, y0) = (fx as isize, fy as isize);
+
+        let mut acc = P::zero();
+        for (dy, wy) in [(0, 1.0 - ay), (1, ay)] {
+            for (dx, wx) in [(0, 1.0 - ax), (1, ax)] {
+                let (sx, sy) = (x0.saturating_add(dx), y0.saturating_add(dy));
+                acc = P::accumulate(acc, self.get_border(sx, sy, border), wx * wy);
+            }
+        }
+        P::finish(acc)
+    }
+
+    /// Bicubically interpolate (Catmull-Rom) at a sub-pixel position, where integer coordinates
+    /// hit pixel centers. Neighbors outside the image are extrapolated according to `border`.
+    pub fn sample_bicubic(&self, x: f32, y: f32, border: Border<P>) -> P {
+        let (fx, fy) = (x.floor(), y.floor());
+        let wx = cubic_weights(x - fx);
+        let wy = cubic_weights(y - fy);
+        let (x0, y0) = (fx as isize, fy as isize);
+
+        let mut acc = P::zero();
+        for (dy, wy) in (-1..=2).zip(wy) {
+            for (dx, wx) in (-1..=2).zip(wx) {
+                let (sx, sy) = (x0.saturating_add(dx), y0.saturating_add(dy));
+                acc = P::accumulate(acc, self.get_border(sx, sy, border), wx * wy);
+            }
+        }
+        P::finish(acc)
+    }
+}
+
+/// Like [`ImageView::sample`], but non-finite coordinates yield the border value instead of
+/// garbage.
+#[inline]
+pub(super) fn sample_finite<P: Interpolate>(
+    image: &ImageView<P>,
+    x: f32,
+    y: f32,
+    interpolation: Interpolation,
+    border: Border<P>,
+) -> P {
+    if x.is_finite() && y.is_finite() {
+        image.sample(x, y, interpolation, border)
+    } else {
+        // Points mapped to infinity are treated as lying far outside the image
+        image.get_border(isize::MIN, isize::MIN, border)
+    }
+}
+
+impl<P: Interpolate + MaybeSend + MaybeSync> ImageView<'_, P> {
+    /// Extract a `size`x`size` patch centered at a sub-pixel position using bilinear
+    /// interpolation. Samples outside the image are extrapolated according to `border`.
+    pub fn patch_bilinear(&self, cx: f32, cy: f32, size: usize, border: Border<P>) -> Image<P> {
+        let half = (size / 2) as f32;
+        let data = par_row_collect(size, size, |x, y| {
+            self.sample_bilinear(cx - half + x as f32, cy - half + y as f32, border)
+        });
+        Image::new(size, size, size, data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pixel::{Bgra, Gray, Rgb};
+
+    fn ramp() -> Image<Gray<f32>> {
+        // value = x + 10 * y
+        let data = (0..4).flat_map(|y| (0..4).map(move |x| Gray::new((x + 10 * y) as f32)));
+        Image::new(4, 4, 4, data.collect())
+    }
+
+    #[test]
+    fn bilinear_interpolates_ramp() {
+        let img = ramp();
+        let view = img.view();
+
+        assert_eq!(view.sample_bilinear(2.0, 1.0, Border::Replicate).value, 12.0);
+        assert!((view.sample_bilinear(1.5, 1.25, Border::Replicate).value - 14.0).abs() < 1e-5);
+        assert!((view.sample_bilinear(0.25, 2.75, Border::Replicate).value - 27.75).abs() < 1e-5);
+    }
+
+    #[test]
+    fn bilinear_out_of_bounds() {
+        let img = ramp();
+        let view = img.view();
+
+        assert_eq!(view.sample_bilinear(-1.0, 0.0, Border::Replicate).value, 0.0);
+        assert_eq!(view.sample_bilinear(-5.0, -5.0, Border::Constant(Gray::new(7.0))).value, 7.0);
+        // Halfway between the edge pixel (3) and the constant (7)
+        let v = view.sample_bilinear(3.5, 0.0, Border::Constant(Gray::new(7.0))).value;
+        assert!((v - 5.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn bicubic_exact_at_centers_and_linear() {
+        let img = ramp();
+        let view = img.view();
+
+        for y in 0..4 {
+            for x in 0..4 {
+                let v = view.sample_bicubic(x as f32, y as f32, Border::Reflect101).value;
+                assert!((v - img.get(x, y).value).abs() < 1e-5);
+            }
+        }
+        // Catmull-Rom reproduces linear functions away from the border
+        assert!((view.sample_bicubic(1.3, 1.6, Border::Reflect101).value - 17.3).abs() < 1e-4);
+    }
+
+    #[test]
+    fn bilinear_rgb() {
+        let img = Image::new(2, 1, 2, vec![Rgb::new(0.0f32, 1.0, 0.5), Rgb::new(1.0, 0.0, 0.5)]);
+        let px = img.view().sample_bilinear(0.25, 0.0, Border::Replicate);
+
+        assert!((px.r - 0.25).abs() < 1e-6);
+        assert!((px.g - 0.75).abs() < 1e-6);
+        assert!((px.b - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn bilinear_u8_rounds_once() {
+        let img = Image::new(2, 1, 2, vec![Gray::new(10u8), Gray::new(13u8)]);
+        let view = img.view();
+
+        assert_eq!(view.sample_bilinear(0.5, 0.0, Border::Replicate).value, 12);
+        assert_eq!(view.sample_bilinear(0.4, 0.0, Border::Replicate).value, 11);
+        assert_eq!(view.sample_nearest(0.6, 0.0, Border::Replicate).value, 13);
+    }
+
+    #[test]
+    fn bilinear_bgra_u16() {
+        let img =
+            Image::new(2, 1, 2, vec![Bgra::new(0u16, 100, 1000, 65535), Bgra::new(10, 0, 0, 0)]);
+        let px = img.view().sample_bilinear(0.5, 0.0, Border::Replicate);
+        assert_eq!(px, Bgra::new(5, 50, 500, 32768));
+    }
+
+    #[test]
+    fn subpixel_patch() {
+        let img = ramp();
+        let patch = img.view().patch_bilinear(1.5, 1.5, 3, Border::Replicate);
+
+        assert_eq!((patch.width(), patch.height()), (3, 3));
+        assert!((patch.get(0, 0).value - 5.5).abs() < 1e-5);
+        assert!((patch.get(1, 1).value - 16.5).abs() < 1e-5);
+        assert!((patch.get(2, 2).value - 27.5).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,190 @@
+use std::marker::PhantomData;
+use std::ptr::NonNull;
+
+use super::types::ImageViewMut;
+
+/// A mutable rectangular tile of an image, handed out by [`ImageViewMut::tiles_mut`].
+///
+/// Tiles of the same image never overlap, so they can be processed on separate threads. Unlike
+/// an [`ImageViewMut`], a tile only gives access to its own rows, since the memory between them
+/// belongs to neighboring tiles.
+#[derive(Debug)]
+pub struct TileMut<'a, P> {
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+    stride: usize,
+    ptr: NonNull<P>,
+    _marker: PhantomData<&'a mut P>,
+}
+
+// SAFETY: a tile has exclusive access to its pixels, like `&mut [P]`.
+unsafe impl<P: Send> Send for TileMut<'_, P> {}
+unsafe impl<P: Sync> Sync for TileMut<'_, P> {}
+
+impl<P> TileMut<'_, P> {
+    /// Column of the tile's top-left corner in the parent view.
+    #[inline]
+    pub fn x(&self) -> usize { self.x }
+
+    /// Row of the tile's top-left corner in the parent view.
+    #[inline]
+    pub fn y(&self) -> usize { self.y }
+
+    #[inline]
+    pub fn width(&self) -> usize { self.width }
+
+    #[inline]
+    pub fn height(&self) -> usize { self.height }
+
+    /// # Panics
+    ///
+    /// Panics if `y` is out of bounds.
+    #[inline]
+    pub fn row(&self, y: usize) -> &[P] {
+        assert!(y < self.height, "Row out of bounds");
+        // SAFETY: row `y` lies within the parent view and belongs to this tile only.
+        unsafe { std::slice::from_raw_parts(self.ptr.as_ptr().add(y * self.stride), self.width) }
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `y` is out of bounds.
+    #[inline]
+    pub fn row_mut(&mut self, y: usize) -> &mut [P] {
+        assert!(y < self.height, "Row out of bounds");
+        // SAFETY: as in `row`, and `&mut self` guarantees exclusive access.
+        unsafe {
+            std::slice::from_raw_parts_mut(self.ptr.as_ptr().add(y * self.stride), self.width)
+        }
+    }
+
+    pub fn rows_mut(&mut self) -> impl Iterator<Item = &mut [P]> {
+        let (width, stride, ptr) = (self.width, self.stride, self.ptr);
+        // SAFETY: rows of a tile are disjoint, and the iterator borrows the tile mutably.
+        (0..self.height).map(move |y| unsafe {
+            std::slice::from_raw_parts_mut(ptr.as_ptr().add(y * stride), width)
+        })
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `(x, y)` is out of bounds.
+    #[inline]
+    pub fn get(&self, x: usize, y: usize) -> &P { &self.row(y)[x] }
+
+    /// # Panics
+    ///
+    /// Panics if `(x, y)` is out of bounds.
+    #[inline]
+    pub fn get_mut(&mut self, x: usize, y: usize) -> &mut P { &mut self.row_mut(y)[x] }
+}
+
+/// Iterator over the tiles of an [`ImageViewMut`] in row-major order.
+#[derive(Debug)]
+pub struct TilesMut<'a, P> {
+    width: usize,
+    height: usize,
+    stride: usize,
+    tile_width: usize,
+    tile_height: usize,
+    next: usize,
+    ptr: NonNull<P>,
+    _marker: PhantomData<&'a mut P>,
+}
+
+// SAFETY: the iterator only hands out disjoint tiles.
+unsafe impl<P: Send> Send for TilesMut<'_, P> {}
+
+impl<'a, P> Iterator for TilesMut<'a, P> {
+    type Item = TileMut<'a, P>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let cols = self.width.div_ceil(self.tile_width);
+        let (tx, ty) = (self.next % cols, self.next / cols);
+        let (x, y) = (tx * self.tile_width, ty * self.tile_height);
+        if y >= self.height {
+            return None;
+        }
+        self.next += 1;
+
+        Some(TileMut {
+            x,
+            y,
+            width: self.tile_width.min(self.width - x),
+            height: self.tile_height.min(self.height - y),
+            stride: self.stride,
+            // SAFETY: (x, y) lies within the view, so the offset stays within its buffer.
+            ptr: unsafe { self.ptr.add(y * self.stride + x) },
+            _marker: PhantomData,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let total = self.width.div_ceil(self.tile_width) * self.height.div_ceil(self.tile_height);
+        let remaining = total - self.next;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<P> ExactSizeIterator for TilesMut<'_, P> {}
+
+impl<P> ImageViewMut<'_, P> {
+    /// Split the view into disjoint `tile_width`x`tile_height` tiles in row-major order. Tiles
+    /// on the right and bottom edges may be smaller.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either tile dimension is zero.
+    pub fn tiles_mut(&mut self, tile_width: usize, tile_height: usize) -> TilesMut<'_, P> {
+        assert!(tile_width > 0 && tile_height > 0, "Tile size must be non-zero");
+        TilesMut {
+            width: self.width(),
+            height: self.height(),
+            stride: self.stride(),
+            tile_width,
+            tile_height,
+            next: 0,
+            ptr: NonNull::from(&mut *self.data_mut()).cast(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::image::Image;
+    use crate::pixel::Gray;
+
+    #[test]
+    fn tiles_cover_image_once() {
+        let mut img = Image::new(5, 3, 6, vec![Gray::new(0u32); 18]);
+        let mut view = img.view_mut();
+
+        let tiles: Vec<_> = view.tiles_mut(2, 2).collect();
+        assert_eq!(tiles.len(), 6);
+        assert_eq!((tiles[2].x(), tiles[2].y(), tiles[2].width(), tiles[2].height()), (4, 0, 1, 2));
+        assert_eq!((tiles[3].x(), tiles[3].y(), tiles[3].width(), tiles[3].height()), (0, 2, 2, 1));
+
+        std::thread::scope(|s| {
+            for mut tile in tiles {
+                s.spawn(move || {
+                    let id = (tile.y() * 10 + tile.x()) as u32;
+                    for row in tile.rows_mut() {
+                        row.iter_mut().for_each(|p| p.value += id + 1);
+                    }
+                });
+            }
+        });
+
+        #[rustfmt::skip]
+        let expected = [
+            1, 1, 3, 3, 5,
+            1, 1, 3, 3, 5,
+            21, 21, 23, 23, 25,
+        ];
+        let values: Vec<u32> = img.view().pixels().map(|p| p.value).collect();
+        assert_eq!(values, expected);
+    }
+}
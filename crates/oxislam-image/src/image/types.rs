@@ -1,4 +1,6 @@
-use crate::pixel::Gray;
+use crate::border::Border;
+use crate::error::ImageError;
+use crate::pixel::{Bgr, Bgra, Gray, Rgb, Rgba};
 
 #[derive(Debug)]
 pub struct Image<P> {
@@ -24,14 +26,42 @@ pub struct ImageViewMut<'a, P> {
     data: &'a mut [P],
 }
 
+/// Check that a buffer of `len` elements can back a `width`x`height` image with the given stride.
+pub(crate) fn validate_layout(
+    width: usize,
+    height: usize,
+    stride: usize,
+    len: usize,
+) -> Result<(), ImageError> {
+    if width == 0 || height == 0 {
+        return Err(ImageError::ZeroSize { width, height });
+    }
+    if stride < width {
+        return Err(ImageError::StrideTooSmall { stride, width });
+    }
+    let required = stride.saturating_mul(height);
+    if len < required {
+        return Err(ImageError::BufferTooShort { len, required });
+    }
+    Ok(())
+}
+
 impl<P> Image<P> {
+    /// # Panics
+    ///
+    /// Panics if the layout is invalid; see [`Image::try_new`].
     pub fn new(width: usize, height: usize, stride: usize, data: Vec<P>) -> Self {
-        assert!(width > 0);
-        assert!(height > 0);
-        assert!(stride >= width);
-        assert!(data.len() >= stride * height);
+        Self::try_new(width, height, stride, data).unwrap_or_else(|e| panic!("{e}"))
+    }
 
-        Self { width, height, stride, data }
+    pub fn try_new(
+        width: usize,
+        height: usize,
+        stride: usize,
+        data: Vec<P>,
+    ) -> Result<Self, ImageError> {
+        validate_layout(width, height, stride, data.len())?;
+        Ok(Self { width, height, stride, data })
     }
 
     #[inline]
@@ -49,6 +79,10 @@ impl<P> Image<P> {
     #[inline]
     pub fn data_mut(&mut self) -> &mut [P] { &mut self.data }
 
+    /// Release the backing buffer, including any row padding.
+    #[inline]
+    pub fn into_data(self) -> Vec<P> { self.data }
+
     #[inline]
     pub fn index(&self, x: usize, y: usize) -> usize {
         debug_assert!(x < self.width);
@@ -80,16 +114,46 @@ impl<P> Image<P> {
             data: &mut self.data,
         }
     }
+
+    /// Borrow a region of the image; see [`ImageView::subview`].
+    pub fn subview(
+        &self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    ) -> Option<ImageView<'_, P>> {
+        self.view().subview(x, y, width, height)
+    }
+
+    /// Mutably borrow a region of the image; see [`ImageViewMut::subview_mut`].
+    pub fn subview_mut(
+        &mut self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    ) -> Option<ImageViewMut<'_, P>> {
+        self.view_mut().into_subview(x, y, width, height)
+    }
 }
 
 impl<'a, P> ImageView<'a, P> {
+    /// # Panics
+    ///
+    /// Panics if the layout is invalid; see [`ImageView::try_new`].
     pub fn new(data: &'a [P], width: usize, height: usize, stride: usize) -> Self {
-        assert!(width > 0);
-        assert!(height > 0);
-        assert!(stride >= width);
-        assert!(data.len() >= stride * height);
+        Self::try_new(data, width, height, stride).unwrap_or_else(|e| panic!("{e}"))
+    }
 
-        Self { width, height, stride, data }
+    pub fn try_new(
+        data: &'a [P],
+        width: usize,
+        height: usize,
+        stride: usize,
+    ) -> Result<Self, ImageError> {
+        validate_layout(width, height, stride, data.len())?;
+        Ok(Self { width, height, stride, data })
     }
 
     #[inline]
@@ -165,14 +229,36 @@ impl<'a, P> ImageView<'a, P> {
     }
 }
 
+impl<P: Copy> ImageView<'_, P> {
+    /// Get the pixel at a possibly out-of-bounds position, extrapolated according to `border`.
+    #[inline]
+    pub fn get_border(&self, x: isize, y: isize, border: Border<P>) -> P {
+        match (border.map_index(x, self.width), border.map_index(y, self.height)) {
+            (Some(x), Some(y)) => *self.get(x, y),
+            _ => match border {
+                Border::Constant(value) => value,
+                _ => unreachable!("only constant borders map outside the image"),
+            },
+        }
+    }
+}
+
 impl<'a, P> ImageViewMut<'a, P> {
+    /// # Panics
+    ///
+    /// Panics if the layout is invalid; see [`ImageViewMut::try_new`].
     pub fn new(data: &'a mut [P], width: usize, height: usize, stride: usize) -> Self {
-        assert!(width > 0);
-        assert!(height > 0);
-        assert!(stride >= width);
-        assert!(data.len() >= stride * height);
+        Self::try_new(data, width, height, stride).unwrap_or_else(|e| panic!("{e}"))
+    }
 
-        Self { width, height, stride, data }
+    pub fn try_new(
+        data: &'a mut [P],
+        width: usize,
+        height: usize,
+        stride: usize,
+    ) -> Result<Self, ImageError> {
+        validate_layout(width, height, stride, data.len())?;
+        Ok(Self { width, height, stride, data })
     }
 
     #[inline]
@@ -208,6 +294,83 @@ impl<'a, P> ImageViewMut<'a, P> {
         let idx = self.index(x, y);
         &mut self.data[idx]
     }
+
+    /// Reborrow as an immutable view.
+    pub fn view(&self) -> ImageView<'_, P> {
+        ImageView { width: self.width, height: self.height, stride: self.stride, data: self.data }
+    }
+
+    /// Mutably borrow a `width`x`height` region with top-left corner `(x, y)`, or `None` if it
+    /// is empty or does not fit.
+    pub fn subview_mut(
+        &mut self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    ) -> Option<ImageViewMut<'_, P>> {
+        let data = &mut *self.data;
+        ImageViewMut { width: self.width, height: self.height, stride: self.stride, data }
+            .into_subview(x, y, width, height)
+    }
+
+    /// Like [`ImageViewMut::subview_mut`], but consumes the view to keep its lifetime.
+    pub fn into_subview(
+        self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    ) -> Option<ImageViewMut<'a, P>> {
+        if width == 0 || height == 0 {
+            return None;
+        }
+        if x + width > self.width || y + height > self.height {
+            return None;
+        }
+
+        let offset = y * self.stride + x;
+        Some(ImageViewMut { width, height, stride: self.stride, data: &mut self.data[offset..] })
+    }
+
+    pub fn rows_mut(&mut self) -> impl Iterator<Item = &mut [P]> {
+        let width = self.width;
+        self.data.chunks_mut(self.stride).take(self.height).map(move |row| &mut row[..width])
+    }
+
+    /// Split into the rows above `row` and the rows from `row` on.
+    ///
+    /// # Panics
+    ///
+    /// Panics unless `0 < row < height`.
+    pub fn split_at_row_mut(&mut self, row: usize) -> (ImageViewMut<'_, P>, ImageViewMut<'_, P>) {
+        assert!(row > 0 && row < self.height, "Split row out of bounds");
+        let (width, stride) = (self.width, self.stride);
+        let (top, bottom) = self.data.split_at_mut(row * stride);
+        (
+            ImageViewMut { width, height: row, stride, data: top },
+            ImageViewMut { width, height: self.height - row, stride, data: bottom },
+        )
+    }
+
+    /// Disjoint full-width bands of `rows` rows each, top to bottom. The last band may be
+    /// shorter.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rows` is zero.
+    pub fn bands_mut(&mut self, rows: usize) -> impl Iterator<Item = ImageViewMut<'_, P>> {
+        assert!(rows > 0, "Band height must be non-zero");
+        let (width, height, stride) = (self.width, self.height, self.stride);
+        self.data.chunks_mut(rows * stride).take(height.div_ceil(rows)).enumerate().map(
+            move |(i, data)| ImageViewMut {
+                width,
+                height: rows.min(height - i * rows),
+                stride,
+                data,
+            },
+        )
+    }
 }
 
 impl<P: Clone> Image<P> {
@@ -219,56 +382,131 @@ impl<P: Clone> Image<P> {
     }
 }
 
-pub trait RawPixel: Copy + 'static {}
-impl RawPixel for u8 {}
-impl RawPixel for f32 {}
-
-impl<T: RawPixel> Image<Gray<T>> {
-    pub fn from_raw(width: usize, height: usize, stride: usize, data: Vec<T>) -> Self {
-        assert!(width > 0);
-        assert!(height > 0);
-        assert!(stride >= width);
-        assert!(data.len() >= stride * height);
-
-        // SAFETY: Gray<T> is repr(transparent), so its layout is identical to T.
-        // We're just reinterpreting the vector's memory to change the type.
-        let gray_data = unsafe {
-            let ptr = data.as_ptr() as *const Gray<T>;
-            let len = data.len();
-            let cap = data.capacity();
-            std::mem::forget(data);
-            Vec::from_raw_parts(ptr as *mut Gray<T>, len, cap)
+/// Primitive channel types.
+///
+/// # Safety
+///
+/// Implementors must have no padding and accept every bit pattern as a valid value.
+pub unsafe trait RawPixel: Copy + 'static {}
+// SAFETY: primitive numeric types without padding or invalid bit patterns.
+unsafe impl RawPixel for u8 {}
+unsafe impl RawPixel for u16 {}
+unsafe impl RawPixel for f32 {}
+unsafe impl RawPixel for f64 {}
+
+/// Pixel types laid out as `CHANNELS` consecutive values of a [`RawPixel`] channel type.
+///
+/// # Safety
+///
+/// `Self` must have the size of `CHANNELS` channel values and the alignment of one.
+pub unsafe trait RawChannels: Copy + 'static {
+    type Channel: RawPixel;
+    const CHANNELS: usize;
+}
+
+// SAFETY: Gray<T> is repr(transparent) over T.
+unsafe impl<T: RawPixel> RawChannels for Gray<T> {
+    type Channel = T;
+    const CHANNELS: usize = 1;
+}
+
+// SAFETY: the color types below are repr(C) with fields of a single type T, so they have no
+// padding and the alignment of T.
+unsafe impl<T: RawPixel> RawChannels for Rgb<T> {
+    type Channel = T;
+    const CHANNELS: usize = 3;
+}
+
+unsafe impl<T: RawPixel> RawChannels for Bgr<T> {
+    type Channel = T;
+    const CHANNELS: usize = 3;
+}
+
+unsafe impl<T: RawPixel> RawChannels for Rgba<T> {
+    type Channel = T;
+    const CHANNELS: usize = 4;
+}
+
+unsafe impl<T: RawPixel> RawChannels for Bgra<T> {
+    type Channel = T;
+    const CHANNELS: usize = 4;
+}
+
+impl<P: RawChannels> Image<P> {
+    /// Build an image from interleaved channel values. `stride` is measured in pixels.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the layout is invalid; see [`Image::try_from_raw`].
+    pub fn from_raw(width: usize, height: usize, stride: usize, data: Vec<P::Channel>) -> Self {
+        Self::try_from_raw(width, height, stride, data).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Build an image from interleaved channel values. `stride` is measured in pixels.
+    ///
+    /// The buffer is reused without copying whenever its capacity is a whole number of pixels,
+    /// which is always the case for single-channel images.
+    pub fn try_from_raw(
+        width: usize,
+        height: usize,
+        stride: usize,
+        data: Vec<P::Channel>,
+    ) -> Result<Self, ImageError> {
+        let channels = P::CHANNELS;
+        if !data.len().is_multiple_of(channels) {
+            return Err(ImageError::PartialPixel { len: data.len(), channels });
+        }
+        validate_layout(width, height, stride, data.len() / channels)?;
+
+        let pixels = if data.capacity().is_multiple_of(channels) {
+            // SAFETY: P has the layout of `channels` consecutive channel values with the same
+            // alignment, and both length and capacity are whole numbers of pixels, so the
+            // allocation can be reinterpreted in place.
+            unsafe {
+                let ptr = data.as_ptr() as *mut P;
+                let len = data.len() / channels;
+                let cap = data.capacity() / channels;
+                std::mem::forget(data);
+                Vec::from_raw_parts(ptr, len, cap)
+            }
+        } else {
+            data.chunks_exact(channels)
+                // SAFETY: each chunk holds exactly one pixel worth of aligned channel values.
+                .map(|c| unsafe { std::ptr::read(c.as_ptr() as *const P) })
+                .collect()
         };
 
-        Self { width, height, stride, data: gray_data }
+        Ok(Self { width, height, stride, data: pixels })
     }
 
-    pub fn into_raw(self) -> (usize, usize, usize, Vec<T>) {
+    pub fn into_raw(self) -> (usize, usize, usize, Vec<P::Channel>) {
         let width = self.width;
         let height = self.height;
         let stride = self.stride;
 
-        // SAFETY: Gray<T> is repr(transparent), so its layout is identical to T.
-        // We're reinterpreting back to the original raw type.
+        // SAFETY: P has the layout of `CHANNELS` consecutive channel values with the same
+        // alignment, so the allocation can be reinterpreted as channel values.
         let raw_data = unsafe {
-            let ptr = self.data.as_ptr() as *const T;
-            let len = self.data.len();
-            let cap = self.data.capacity();
+            let ptr = self.data.as_ptr() as *mut P::Channel;
+            let len = self.data.len() * P::CHANNELS;
+            let cap = self.data.capacity() * P::CHANNELS;
             std::mem::forget(self.data);
-            Vec::from_raw_parts(ptr as *mut T, len, cap)
+            Vec::from_raw_parts(ptr, len, cap)
         };
 
         (width, height, stride, raw_data)
     }
 
-    pub fn as_raw(&self) -> &[T] {
-        // SAFETY: Gray<T> is repr(transparent), so a &[Gray<T>] can be safely reinterpreted as &[T].
-        unsafe { std::slice::from_raw_parts(self.data.as_ptr() as *const T, self.data.len()) }
+    pub fn as_raw(&self) -> &[P::Channel] {
+        let len = self.data.len() * P::CHANNELS;
+        // SAFETY: P has the layout of `CHANNELS` consecutive channel values.
+        unsafe { std::slice::from_raw_parts(self.data.as_ptr() as *const P::Channel, len) }
     }
 
-    pub fn as_raw_mut(&mut self) -> &mut [T] {
-        // SAFETY: Gray<T> is repr(transparent), so &mut [Gray<T>] can be safely reinterpreted as &mut [T].
-        unsafe { std::slice::from_raw_parts_mut(self.data.as_mut_ptr() as *mut T, self.data.len()) }
+    pub fn as_raw_mut(&mut self) -> &mut [P::Channel] {
+        let len = self.data.len() * P::CHANNELS;
+        // SAFETY: P has the layout of `CHANNELS` consecutive channel values.
+        unsafe { std::slice::from_raw_parts_mut(self.data.as_mut_ptr() as *mut P::Channel, len) }
     }
 }
 
@@ -294,6 +532,48 @@ mod tests {
         assert_eq!(raw, data);
     }
 
+    #[test]
+    fn rgba_from_raw_roundtrip() {
+        let data: Vec<u8> = (0..16).collect();
+        let img = Image::<Rgba<u8>>::from_raw(2, 2, 2, data.clone());
+
+        assert_eq!(*img.get(1, 0), Rgba::new(4, 5, 6, 7));
+        assert_eq!(*img.get(0, 1), Rgba::new(8, 9, 10, 11));
+        assert_eq!(img.as_raw(), &data[..]);
+        assert_eq!(img.into_raw().3, data);
+
+        // Capacity that is not a whole number of pixels falls back to copying
+        let mut data: Vec<u16> = Vec::with_capacity(7);
+        data.extend([1, 2, 3, 4, 5, 6]);
+        let img = Image::<Bgr<u16>>::from_raw(2, 1, 2, data);
+        assert_eq!(*img.get(1, 0), Bgr::new(4, 5, 6));
+
+        assert_eq!(
+            Image::<Rgb<f64>>::try_from_raw(1, 1, 1, vec![0.0; 4]).unwrap_err(),
+            ImageError::PartialPixel { len: 4, channels: 3 }
+        );
+    }
+
+    #[test]
+    fn try_new_reports_invalid_layouts() {
+        assert_eq!(
+            Image::try_new(0, 2, 0, Vec::<Gray<u8>>::new()).unwrap_err(),
+            ImageError::ZeroSize { width: 0, height: 2 }
+        );
+        assert_eq!(
+            Image::try_new(3, 2, 2, vec![Gray::new(0u8); 6]).unwrap_err(),
+            ImageError::StrideTooSmall { stride: 2, width: 3 }
+        );
+        assert_eq!(
+            Image::<Gray<f32>>::try_from_raw(3, 2, 4, vec![0.0; 7]).unwrap_err(),
+            ImageError::BufferTooShort { len: 7, required: 8 }
+        );
+
+        let mut data = vec![Gray::new(0u8); 8];
+        assert!(ImageView::try_new(&data, 3, 2, 4).is_ok());
+        assert!(ImageViewMut::try_new(&mut data, 3, 3, 3).is_err());
+    }
+
     #[test]
     fn subview_stride_correctness() {
         // 4x4 image with stride=5 (one padding column per row)
@@ -319,4 +599,39 @@ mod tests {
         assert_eq!(sub.get(0, 1).value, 10.0);
         assert_eq!(sub.get(1, 1).value, 11.0);
     }
+
+    #[test]
+    fn mutable_subviews_and_rows() {
+        let mut img = Image::filled(4, 3, Gray::new(0u8));
+
+        let mut sub = img.subview_mut(1, 1, 2, 2).unwrap();
+        for (y, row) in sub.rows_mut().enumerate() {
+            row.fill(Gray::new(y as u8 + 1));
+        }
+        assert!(img.subview_mut(3, 0, 2, 1).is_none());
+
+        let rows: Vec<Vec<u8>> =
+            img.view().rows().map(|row| row.iter().map(|p| p.value).collect()).collect();
+        assert_eq!(rows, [[0, 0, 0, 0], [0, 1, 1, 0], [0, 2, 2, 0]]);
+        assert_eq!(img.subview(1, 2, 2, 1).unwrap().get(1, 0).value, 2);
+    }
+
+    #[test]
+    fn split_rows_across_threads() {
+        let mut img = Image::new(3, 5, 4, vec![Gray::new(0u16); 20]);
+        let mut view = img.view_mut();
+
+        let (mut top, mut bottom) = view.split_at_row_mut(2);
+        assert_eq!((top.height(), bottom.height()), (2, 3));
+        std::thread::scope(|s| {
+            s.spawn(|| top.rows_mut().for_each(|row| row.fill(Gray::new(1))));
+            s.spawn(|| bottom.rows_mut().for_each(|row| row.fill(Gray::new(2))));
+        });
+
+        let heights: Vec<usize> = view.bands_mut(2).map(|band| band.height()).collect();
+        assert_eq!(heights, [2, 2, 1]);
+        assert_eq!(img.get(2, 1).value, 1);
+        assert_eq!(img.get(0, 2).value, 2);
+        assert_eq!(img.data()[3].value, 0); // padding is untouched
+    }
 }
@@ -0,0 +1,188 @@
+use oxislam_geometry::{Matrix2x3, Matrix3};
+
+use super::sample::{Interpolate, Interpolation, sample_finite};
+use super::types::{Image, ImageView};
+use crate::border::Border;
+use crate::parallel::{MaybeSend, MaybeSync, par_row_collect};
+
+// Closed-form inverse via the adjugate; nalgebra's `try_inverse` needs its `std` or `libm`
+// feature, which oxislam-geometry does not enable.
+fn invert(m: &Matrix3<f32>) -> Option<Matrix3<f32>> {
+    let cofactor = |r0: usize, r1: usize, c0: usize, c1: usize| {
+        m[(r0, c0)] * m[(r1, c1)] - m[(r0, c1)] * m[(r1, c0)]
+    };
+    #[rustfmt::skip]
+    let adj = Matrix3::new(
+        cofactor(1, 2, 1, 2), -cofactor(0, 2, 1, 2), cofactor(0, 1, 1, 2),
+        -cofactor(1, 2, 0, 2), cofactor(0, 2, 0, 2), -cofactor(0, 1, 0, 2),
+        cofactor(1, 2, 0, 1), -cofactor(0, 2, 0, 1), cofactor(0, 1, 0, 1),
+    );
+
+    let det = m[(0, 0)] * adj[(0, 0)] + m[(0, 1)] * adj[(1, 0)] + m[(0, 2)] * adj[(2, 0)];
+    (det != 0.0 && det.is_finite()).then(|| adj / det)
+}
+
+/// Warp an image with an affine transform mapping input coordinates to output coordinates.
+///
+/// The output is `width`x`height`; each output pixel is sampled from the input at the inverse
+/// transformed position, extrapolating according to `border`.
+///
+/// # Panics
+///
+/// Panics if `transform` is not invertible.
+pub fn warp_affine<P>(
+    image: &ImageView<P>,
+    transform: &Matrix2x3<f32>,
+    width: usize,
+    height: usize,
+    interpolation: Interpolation,
+    border: Border<P>,
+) -> Image<P>
+where
+    P: Interpolate + MaybeSend + MaybeSync,
+{
+    let m = transform;
+    #[rustfmt::skip]
+    let homogeneous = Matrix3::new(
+        m[(0, 0)], m[(0, 1)], m[(0, 2)],
+        m[(1, 0)], m[(1, 1)], m[(1, 2)],
+        0.0, 0.0, 1.0,
+    );
+    let inv = invert(&homogeneous).expect("Affine transform must be invertible");
+
+    let data = par_row_collect(width, height, |x, y| {
+        let (x, y) = (x as f32, y as f32);
+        let sx = inv[(0, 0)] * x + inv[(0, 1)] * y + inv[(0, 2)];
+        let sy = inv[(1, 0)] * x + inv[(1, 1)] * y + inv[(1, 2)];
+        sample_finite(image, sx, sy, interpolation, border)
+    });
+
+    Image::new(width, height, width, data)
+}
+
+/// Warp an image with a homography mapping input coordinates to output coordinates.
+///
+/// The output is `width`x`height`; each output pixel is sampled from the input at the inverse
+/// transformed position, extrapolating according to `border`.
+///
+/// # Panics
+///
+/// Panics if `transform` is not invertible.
+pub fn warp_perspective<P>(
+    image: &ImageView<P>,
+    transform: &Matrix3<f32>,
+    width: usize,
+    height: usize,
+    interpolation: Interpolation,
+    border: Border<P>,
+) -> Image<P>
+where
+    P: Interpolate + MaybeSend + MaybeSync,
+{
+    let inv = invert(transform).expect("Homography must be invertible");
+
+    let data = par_row_collect(width, height, |x, y| {
+        let (x, y) = (x as f32, y as f32);
+        let w = inv[(2, 0)] * x + inv[(2, 1)] * y + inv[(2, 2)];
+        let sx = (inv[(0, 0)] * x + inv[(0, 1)] * y + inv[(0, 2)]) / w;
+        let sy = (inv[(1, 0)] * x + inv[(1, 1)] * y + inv[(1, 2)]) / w;
+        sample_finite(image, sx, sy, interpolation, border)
+    });
+
+    Image::new(width, height, width, data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pixel::Gray;
+
+    fn numbered(width: usize, height: usize) -> Image<Gray<f32>> {
+        let data = (0..width * height).map(|v| Gray::new(v as f32)).collect();
+        Image::new(width, height, width, data)
+    }
+
+    fn values(img: &Image<Gray<f32>>) -> Vec<f32> { img.view().pixels().map(|p| p.value).collect() }
+
+    #[test]
+    fn inverse_of_homography() {
+        #[rustfmt::skip]
+        let m = Matrix3::new(
+            2.0, 0.5, -1.0,
+            0.1, 1.5, 3.0,
+            0.01, -0.02, 1.0,
+        );
+        let identity = m * invert(&m).unwrap();
+        for r in 0..3 {
+            for c in 0..3 {
+                let expected = if r == c { 1.0 } else { 0.0 };
+                assert!((identity[(r, c)] - expected).abs() < 1e-5);
+            }
+        }
+        assert!(invert(&Matrix3::new(1.0, 2.0, 3.0, 2.0, 4.0, 6.0, 0.0, 0.0, 1.0)).is_none());
+    }
+
+    #[test]
+    fn affine_translation() {
+        let img = numbered(3, 2);
+        let shift = Matrix2x3::new(1.0, 0.0, 1.0, 0.0, 1.0, 0.0);
+        let zero = Border::Constant(Gray::new(-1.0));
+
+        let out = warp_affine(&img.view(), &shift, 3, 2, Interpolation::Nearest, zero);
+        assert_eq!(values(&out), [-1.0, 0.0, 1.0, -1.0, 3.0, 4.0]);
+    }
+
+    #[test]
+    fn affine_rotation_90() {
+        // [0 1 2]      [3 0]
+        // [3 4 5]  ->  [4 1]
+        //              [5 2]
+        let img = numbered(3, 2);
+        // (x, y) -> (1 - y, x)
+        let rot = Matrix2x3::new(0.0, -1.0, 1.0, 1.0, 0.0, 0.0);
+
+        let out = warp_affine(&img.view(), &rot, 2, 3, Interpolation::Bilinear, Border::Replicate);
+        assert_eq!(values(&out), [3.0, 0.0, 4.0, 1.0, 5.0, 2.0]);
+    }
+
+    #[test]
+    fn perspective_matches_affine() {
+        let img = numbered(5, 4);
+        let affine = Matrix2x3::new(0.9, 0.1, 0.3, -0.2, 1.1, 0.5);
+        #[rustfmt::skip]
+        let homography = Matrix3::new(
+            0.9, 0.1, 0.3,
+            -0.2, 1.1, 0.5,
+            0.0, 0.0, 1.0,
+        );
+
+        let a = warp_affine(&img.view(), &affine, 5, 4, Interpolation::Bicubic, Border::Reflect);
+        let p = warp_perspective(
+            &img.view(),
+            &homography,
+            5,
+            4,
+            Interpolation::Bicubic,
+            Border::Reflect,
+        );
+        for (a, p) in values(&a).iter().zip(values(&p)) {
+            assert!((a - p).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn perspective_projective_scaling() {
+        let img = numbered(4, 4);
+        // Scaling by the homogeneous coordinate halves the image
+        #[rustfmt::skip]
+        let homography = Matrix3::new(
+            1.0, 0.0, 0.0,
+            0.0, 1.0, 0.0,
+            0.0, 0.0, 2.0,
+        );
+
+        let out =
+            warp_perspective(&img.view(), &homography, 2, 2, Interpolation::Nearest, Border::Wrap);
+        assert_eq!(values(&out), [0.0, 2.0, 8.0, 10.0]);
+    }
+}
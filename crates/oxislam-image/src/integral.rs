@@ -0,0 +1,130 @@
+//! Summed-area tables for constant-time rectangle sums.
+
+use crate::image::ImageView;
+use crate::pixel::{Channel, Gray};
+
+/// Pixels that can be accumulated into an [`IntegralImage`].
+pub trait IntegralPixel: Copy {
+    fn to_f64(self) -> f64;
+}
+
+impl<T: Channel> IntegralPixel for Gray<T> {
+    #[inline]
+    fn to_f64(self) -> f64 { self.value.to_f64() }
+}
+
+/// Summed-area table with `f64` accumulation.
+///
+/// Entry `(x, y)` holds the sum of all pixels above and to the left of `(x, y)`, so the table is
+/// one larger than the image in each dimension. The squared-sum table is only present when built
+/// with [`IntegralImage::with_squares`].
+#[derive(Debug, Clone)]
+pub struct IntegralImage {
+    width: usize,
+    height: usize,
+    sum: Vec<f64>,
+    sq_sum: Option<Vec<f64>>,
+}
+
+impl IntegralImage {
+    pub fn new<P: IntegralPixel>(image: &ImageView<P>) -> Self { Self::build(image, false) }
+
+    /// Build both the sum and the squared-sum tables.
+    pub fn with_squares<P: IntegralPixel>(image: &ImageView<P>) -> Self { Self::build(image, true) }
+
+    fn build<P: IntegralPixel>(image: &ImageView<P>, squares: bool) -> Self {
+        let w = image.width();
+        let h = image.height();
+        let stride = w + 1;
+
+        let mut sum = vec![0.0; stride * (h + 1)];
+        let mut sq_sum = squares.then(|| vec![0.0; stride * (h + 1)]);
+
+        for (y, row) in image.rows().enumerate() {
+            let mut row_sum = 0.0;
+            let mut row_sq_sum = 0.0;
+            for (x, px) in row.iter().enumerate() {
+                let v = px.to_f64();
+                row_sum += v;
+                let idx = (y + 1) * stride + x + 1;
+                sum[idx] = sum[idx - stride] + row_sum;
+                if let Some(sq_sum) = &mut sq_sum {
+                    row_sq_sum += v * v;
+                    sq_sum[idx] = sq_sum[idx - stride] + row_sq_sum;
+                }
+            }
+        }
+
+        Self { width: w, height: h, sum, sq_sum }
+    }
+
+    /// Width of the source image.
+    #[inline]
+    pub fn width(&self) -> usize { self.width }
+
+    /// Height of the source image.
+    #[inline]
+    pub fn height(&self) -> usize { self.height }
+
+    #[inline]
+    pub fn has_squares(&self) -> bool { self.sq_sum.is_some() }
+
+    #[inline]
+    fn rect(table: &[f64], stride: usize, x: usize, y: usize, w: usize, h: usize) -> f64 {
+        let (x1, y1) = (x + w, y + h);
+        table[y1 * stride + x1] - table[y * stride + x1] - table[y1 * stride + x]
+            + table[y * stride + x]
+    }
+
+    /// Sum of the `w`x`h` rectangle with top-left corner `(x, y)`.
+    #[inline]
+    pub fn rect_sum(&self, x: usize, y: usize, w: usize, h: usize) -> f64 {
+        assert!(x + w <= self.width && y + h <= self.height, "Rectangle out of bounds");
+        Self::rect(&self.sum, self.width + 1, x, y, w, h)
+    }
+
+    /// Sum of squares of the `w`x`h` rectangle with top-left corner `(x, y)`, or `None` if the
+    /// squared-sum table was not built.
+    #[inline]
+    pub fn rect_sq_sum(&self, x: usize, y: usize, w: usize, h: usize) -> Option<f64> {
+        assert!(x + w <= self.width && y + h <= self.height, "Rectangle out of bounds");
+        let sq_sum = self.sq_sum.as_ref()?;
+        Some(Self::rect(sq_sum, self.width + 1, x, y, w, h))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::image::Image;
+
+    #[test]
+    fn rect_sums_match_brute_force() {
+        let data: Vec<Gray<u8>> = (0..35).map(|v| Gray::new((v * 13 % 17) as u8)).collect();
+        let img = Image::new(7, 5, 7, data);
+        let integral = IntegralImage::with_squares(&img.view());
+
+        for (x, y, w, h) in [(0, 0, 7, 5), (2, 1, 3, 2), (6, 4, 1, 1), (3, 2, 0, 2)] {
+            let (mut sum, mut sq_sum) = (0.0, 0.0);
+            for yy in y..y + h {
+                for xx in x..x + w {
+                    let v = img.get(xx, yy).value as f64;
+                    sum += v;
+                    sq_sum += v * v;
+                }
+            }
+            assert_eq!(integral.rect_sum(x, y, w, h), sum);
+            assert_eq!(integral.rect_sq_sum(x, y, w, h), Some(sq_sum));
+        }
+    }
+
+    #[test]
+    fn squares_are_optional() {
+        let img = Image::filled(3, 3, Gray::new(0.5f32));
+        let integral = IntegralImage::new(&img.view());
+
+        assert!(!integral.has_squares());
+        assert_eq!(integral.rect_sum(0, 0, 3, 3), 4.5);
+        assert_eq!(integral.rect_sq_sum(0, 0, 3, 3), None);
+    }
+}
@@ -0,0 +1,155 @@
+//! Conversions to and from the `image` crate's buffers, enabled by the `image` feature.
+//!
+//! Owned conversions reuse the allocation when the layouts match, which is always the case for
+//! `image` buffers and for our images whose stride equals their width. Borrowed buffers convert
+//! to [`ImageView`]s without copying. Conversions from `image` buffers are fallible, since those
+//! may be empty.
+
+use ::image::{ImageBuffer, Luma, Primitive};
+
+use crate::error::ImageError;
+use crate::image::{Image, ImageView, RawChannels};
+use crate::pixel::{Gray, Rgb, Rgba};
+
+/// Pixel types with a matching `image` crate pixel of identical channel layout.
+pub trait ForeignPixel: RawChannels<Channel: Primitive> {
+    type Foreign: ::image::Pixel<Subpixel = Self::Channel>;
+}
+
+impl ForeignPixel for Gray<u8> {
+    type Foreign = Luma<u8>;
+}
+
+impl ForeignPixel for Gray<u16> {
+    type Foreign = Luma<u16>;
+}
+
+impl ForeignPixel for Gray<f32> {
+    type Foreign = Luma<f32>;
+}
+
+impl ForeignPixel for Rgb<u8> {
+    type Foreign = ::image::Rgb<u8>;
+}
+
+impl ForeignPixel for Rgb<u16> {
+    type Foreign = ::image::Rgb<u16>;
+}
+
+impl ForeignPixel for Rgb<f32> {
+    type Foreign = ::image::Rgb<f32>;
+}
+
+impl ForeignPixel for Rgba<u8> {
+    type Foreign = ::image::Rgba<u8>;
+}
+
+impl ForeignPixel for Rgba<u16> {
+    type Foreign = ::image::Rgba<u16>;
+}
+
+impl ForeignPixel for Rgba<f32> {
+    type Foreign = ::image::Rgba<f32>;
+}
+
+/// `image` buffer type matching the pixel type `P`.
+pub type ForeignImage<P> =
+    ImageBuffer<<P as ForeignPixel>::Foreign, Vec<<P as RawChannels>::Channel>>;
+
+impl<P: ForeignPixel> TryFrom<ForeignImage<P>> for Image<P> {
+    type Error = ImageError;
+
+    /// Fails for empty buffers, which the `image` crate allows but [`Image`] does not.
+    fn try_from(buffer: ForeignImage<P>) -> Result<Self, ImageError> {
+        let (w, h) = (buffer.width() as usize, buffer.height() as usize);
+        let mut raw = buffer.into_raw();
+        raw.truncate(w * h * P::CHANNELS);
+        Image::try_from_raw(w, h, w, raw)
+    }
+}
+
+impl<'a, P: ForeignPixel> TryFrom<&'a ForeignImage<P>> for ImageView<'a, P> {
+    type Error = ImageError;
+
+    /// Fails for empty buffers, like the owned conversion.
+    fn try_from(buffer: &'a ForeignImage<P>) -> Result<Self, ImageError> {
+        let (w, h) = (buffer.width() as usize, buffer.height() as usize);
+        let raw = &buffer.as_raw()[..w * h * P::CHANNELS];
+        // SAFETY: P has the layout of `CHANNELS` consecutive channel values with the same
+        // alignment, and `raw` holds exactly `w * h` pixels.
+        let data = unsafe { std::slice::from_raw_parts(raw.as_ptr() as *const P, w * h) };
+        ImageView::try_new(data, w, h, w)
+    }
+}
+
+impl<P: ForeignPixel> From<Image<P>> for ForeignImage<P> {
+    /// # Panics
+    ///
+    /// Panics if the width or height does not fit in a `u32`.
+    fn from(image: Image<P>) -> Self {
+        let (w, h) = (image.width(), image.height());
+        let raw = if image.stride() == w {
+            let mut raw = image.into_raw().3;
+            raw.truncate(w * h * P::CHANNELS);
+            raw
+        } else {
+            let view = image.view();
+            let mut raw = Vec::with_capacity(w * h * P::CHANNELS);
+            for row in view.rows() {
+                // SAFETY: P has the layout of `CHANNELS` consecutive channel values.
+                let row = unsafe {
+                    std::slice::from_raw_parts(row.as_ptr() as *const P::Channel, w * P::CHANNELS)
+                };
+                raw.extend_from_slice(row);
+            }
+            raw
+        };
+
+        let size = |n: usize| u32::try_from(n).expect("Image dimensions exceed u32");
+        ImageBuffer::from_raw(size(w), size(h), raw).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::image::{GrayImage, RgbImage};
+
+    use super::*;
+
+    #[test]
+    fn gray_roundtrip_reuses_buffer() {
+        let buffer = GrayImage::from_fn(3, 2, |x, y| Luma([(x + 10 * y) as u8]));
+        let ptr = buffer.as_raw().as_ptr();
+
+        let view = ImageView::<Gray<u8>>::try_from(&buffer).unwrap();
+        assert_eq!(view.get(2, 1).value, 12);
+
+        let image = Image::<Gray<u8>>::try_from(buffer).unwrap();
+        assert_eq!(image.data().as_ptr() as *const u8, ptr);
+        assert_eq!(image.get(1, 1).value, 11);
+
+        let buffer = GrayImage::from(image);
+        assert_eq!(buffer.as_raw().as_ptr(), ptr);
+        assert_eq!(buffer.get_pixel(2, 0).0, [2]);
+    }
+
+    #[test]
+    fn rgb_and_float_conversions() {
+        let rgb = RgbImage::from_fn(2, 2, |x, y| ::image::Rgb([x as u8, y as u8, 7]));
+        let image = Image::<Rgb<u8>>::try_from(rgb).unwrap();
+        assert_eq!(*image.get(1, 0), Rgb::new(1, 0, 7));
+
+        let empty = GrayImage::new(0, 0);
+        assert!(matches!(
+            ImageView::<Gray<u8>>::try_from(&empty),
+            Err(ImageError::ZeroSize { .. })
+        ));
+        assert!(matches!(Image::<Gray<u8>>::try_from(empty), Err(ImageError::ZeroSize { .. })));
+
+        // Padded stride is packed on the way out
+        let data = vec![0.25, 0.5, -1.0, 0.75, 1.0, -1.0].into_iter().map(Gray::new).collect();
+        let padded: Image<Gray<f32>> = Image::new(2, 2, 3, data);
+        let buffer = ImageBuffer::<Luma<f32>, Vec<f32>>::from(padded);
+        assert_eq!(buffer.as_raw(), &[0.25, 0.5, 0.75, 1.0]);
+    }
+}
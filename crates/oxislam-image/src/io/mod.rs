@@ -0,0 +1,213 @@
+//! Reading and writing PGM, PPM and PFM images.
+//!
+//! Supported pixel types and formats:
+//!
+//! | Pixel       | Reads                                   | Writes        |
+//! |-------------|-----------------------------------------|---------------|
+//! | `Gray<u8>`  | PGM with maxval up to 255               | 8-bit PGM     |
+//! | `Gray<u16>` | PGM                                     | 16-bit PGM    |
+//! | `Gray<f32>` | grayscale PFM, PGM normalized by maxval | grayscale PFM |
+//! | `Rgb<u8>`   | PPM with maxval up to 255               | 8-bit PPM     |
+//!
+//! Integer samples are read as stored, without rescaling to the full channel range.
+
+mod netpbm;
+
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use crate::error::ImageError;
+use crate::image::{Image, ImageView};
+
+/// Sample encoding of PGM and PPM files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Encoding {
+    /// Raw samples (`P5`, `P6`), big-endian for 16-bit data.
+    #[default]
+    Binary,
+    /// Whitespace-separated decimal samples (`P2`, `P3`).
+    Ascii,
+}
+
+/// Errors from reading or writing image files.
+#[derive(Debug)]
+pub enum IoError {
+    Io(io::Error),
+    /// The file ended before the header or all samples were read.
+    UnexpectedEof,
+    /// The file does not start with a known magic number.
+    UnknownMagic([u8; 2]),
+    InvalidHeader(String),
+    /// A sample is malformed or exceeds the maxval.
+    InvalidSample(String),
+    /// The file holds a format that cannot be stored in the requested pixel type.
+    Incompatible {
+        found: String,
+        target: &'static str,
+    },
+    Unsupported(&'static str),
+    Image(ImageError),
+}
+
+impl fmt::Display for IoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IoError::Io(e) => write!(f, "{e}"),
+            IoError::UnexpectedEof => write!(f, "unexpected end of file"),
+            IoError::UnknownMagic(magic) => {
+                write!(f, "unknown magic number `{}`", magic.escape_ascii())
+            }
+            IoError::InvalidHeader(msg) => write!(f, "invalid header: {msg}"),
+            IoError::InvalidSample(msg) => write!(f, "invalid sample: {msg}"),
+            IoError::Incompatible { found, target } => {
+                write!(f, "cannot read {found} into {target}")
+            }
+            IoError::Unsupported(msg) => write!(f, "unsupported: {msg}"),
+            IoError::Image(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for IoError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            IoError::Io(e) => Some(e),
+            IoError::Image(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for IoError {
+    fn from(e: io::Error) -> Self {
+        match e.kind() {
+            io::ErrorKind::UnexpectedEof => IoError::UnexpectedEof,
+            _ => IoError::Io(e),
+        }
+    }
+}
+
+impl From<ImageError> for IoError {
+    fn from(e: ImageError) -> Self { IoError::Image(e) }
+}
+
+/// Pixel types that can be read from and written to PGM, PPM or PFM files.
+pub trait NetpbmPixel: netpbm::Codec {}
+
+impl<P: netpbm::Codec> NetpbmPixel for P {}
+
+/// Decode an image, detecting the format from its magic number.
+pub fn read<P: NetpbmPixel>(mut reader: impl BufRead) -> Result<Image<P>, IoError> {
+    P::from_netpbm(netpbm::decode(&mut reader)?)
+}
+
+/// Encode an image. Float images are always written as binary PFM and reject
+/// [`Encoding::Ascii`].
+pub fn write<P: NetpbmPixel>(
+    image: &ImageView<P>,
+    mut writer: impl Write,
+    encoding: Encoding,
+) -> Result<(), IoError> {
+    netpbm::encode(&P::to_netpbm(image), &mut writer, encoding)
+}
+
+pub fn load<P: NetpbmPixel>(path: impl AsRef<Path>) -> Result<Image<P>, IoError> {
+    read(BufReader::new(File::open(path)?))
+}
+
+/// Write an image to a file with binary encoding.
+pub fn save<P: NetpbmPixel>(image: &ImageView<P>, path: impl AsRef<Path>) -> Result<(), IoError> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write(image, &mut writer, Encoding::Binary)?;
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pixel::{Gray, Rgb};
+
+    fn roundtrip<P: NetpbmPixel>(image: &Image<P>, encoding: Encoding) -> Image<P> {
+        let mut buf = Vec::new();
+        write(&image.view(), &mut buf, encoding).unwrap();
+        read(buf.as_slice()).unwrap()
+    }
+
+    #[test]
+    fn roundtrips_are_lossless() {
+        let gray: Image<Gray<u8>> =
+            Image::new(3, 2, 3, (0..6).map(|v| Gray::new(v * 50)).collect());
+        let wide: Image<Gray<u16>> =
+            Image::new(2, 2, 2, [0, 255, 256, 65535].map(Gray::new).to_vec());
+        let float: Image<Gray<f32>> =
+            Image::new(2, 3, 2, (0..6).map(|v| Gray::new(v as f32 * -0.37)).collect());
+        let rgb: Image<Rgb<u8>> =
+            Image::new(2, 1, 2, vec![Rgb::new(1, 2, 3), Rgb::new(250, 128, 0)]);
+
+        for encoding in [Encoding::Binary, Encoding::Ascii] {
+            assert_eq!(roundtrip(&gray, encoding).data(), gray.data());
+            assert_eq!(roundtrip(&wide, encoding).data(), wide.data());
+            assert_eq!(roundtrip(&rgb, encoding).data(), rgb.data());
+        }
+        assert_eq!(roundtrip(&float, Encoding::Binary).data(), float.data());
+    }
+
+    #[test]
+    fn reads_ascii_with_comments() {
+        let file = b"P2\n# created by hand\n3 2 # size\n15\n0 1 2\n13 14 15\n";
+
+        let image: Image<Gray<u8>> = read(&file[..]).unwrap();
+        assert_eq!(image.width(), 3);
+        assert_eq!(image.get(2, 1).value, 15);
+
+        let normalized: Image<Gray<f32>> = read(&file[..]).unwrap();
+        assert_eq!(normalized.get(2, 1).value, 1.0);
+    }
+
+    #[test]
+    fn reads_big_endian_pfm_bottom_up() {
+        let mut file = b"Pf\n1 2\n1.0\n".to_vec();
+        file.extend(1.5f32.to_be_bytes());
+        file.extend(2.5f32.to_be_bytes());
+
+        let image: Image<Gray<f32>> = read(file.as_slice()).unwrap();
+        assert_eq!(image.get(0, 0).value, 2.5);
+        assert_eq!(image.get(0, 1).value, 1.5);
+    }
+
+    #[test]
+    fn rejects_oversized_headers() {
+        let err = |file: &[u8]| read::<Gray<f32>>(file).unwrap_err().to_string();
+
+        // Sample count fits, byte count overflows
+        assert_eq!(
+            err(b"Pf\n4611686018427387904 1\n-1.0\n\x00\x00"),
+            "invalid header: image size 4611686018427387904x1 too large"
+        );
+        // Claimed sizes are not allocated up front
+        assert_eq!(err(b"P5\n100000 100000\n255\n\x01\x02"), "unexpected end of file");
+        assert_eq!(err(b"P5\n100000 100000\n65535\n\x01\x02"), "unexpected end of file");
+        assert_eq!(err(b"Pf\n100000 100000\n-1.0\n\x01\x02"), "unexpected end of file");
+    }
+
+    #[test]
+    fn reports_errors() {
+        let err = |file: &[u8]| read::<Gray<u8>>(file).unwrap_err().to_string();
+
+        assert_eq!(err(b"P7\n1 1\n255\n"), "unknown magic number `P7`");
+        assert_eq!(err(b"P5\n2 2\n255\n\x01\x02"), "unexpected end of file");
+        assert_eq!(err(b"P5\nfour 2\n255\n"), "invalid header: invalid width `four`");
+        assert_eq!(err(b"P2\n1 1\n10\n11\n"), "invalid sample: 11 exceeds maxval 10");
+        assert_eq!(err(b"P5\n1 1\n65535\n\x01\x00"), "cannot read 16-bit PGM into Gray<u8>");
+        assert_eq!(err(b"P6\n1 1\n255\nabc"), "cannot read PPM into Gray<u8>");
+
+        let float = Image::filled(1, 1, Gray::new(0.5f32));
+        assert!(matches!(
+            write(&float.view(), Vec::new(), Encoding::Ascii),
+            Err(IoError::Unsupported(_))
+        ));
+    }
+}
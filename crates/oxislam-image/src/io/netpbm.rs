@@ -0,0 +1,313 @@
+use std::io::{BufRead, Read, Write};
+use std::str::FromStr;
+
+use super::{Encoding, IoError};
+use crate::error::ImageError;
+use crate::image::{Image, ImageView};
+use crate::pixel::{Gray, Rgb};
+
+/// Maximum number of samples per line in ASCII output, keeping lines under 70 characters.
+const ASCII_SAMPLES_PER_LINE: usize = 10;
+
+/// Decoded PNM or PFM raster with rows stored top to bottom.
+#[derive(Debug)]
+pub struct Netpbm {
+    pub width: usize,
+    pub height: usize,
+    pub channels: usize,
+    pub samples: Samples,
+}
+
+#[derive(Debug)]
+pub enum Samples {
+    Int { maxval: u16, values: Vec<u16> },
+    Float(Vec<f32>),
+}
+
+impl Netpbm {
+    /// Short description of the format for error messages, e.g. `16-bit PGM`.
+    fn describe(&self) -> String {
+        let kind = match (self.channels, &self.samples) {
+            (1, Samples::Int { .. }) => "PGM",
+            (_, Samples::Int { .. }) => "PPM",
+            (1, Samples::Float(_)) => "grayscale PFM",
+            (_, Samples::Float(_)) => "color PFM",
+        };
+        match self.samples {
+            Samples::Int { maxval, .. } if maxval > 255 => format!("16-bit {kind}"),
+            _ => kind.to_string(),
+        }
+    }
+
+    fn incompatible<P>(&self, target: &'static str) -> Result<Image<P>, IoError> {
+        Err(IoError::Incompatible { found: self.describe(), target })
+    }
+}
+
+/// Conversion between decoded rasters and images of a concrete pixel type.
+pub trait Codec: Sized {
+    fn from_netpbm(netpbm: Netpbm) -> Result<Image<Self>, IoError>;
+
+    fn to_netpbm(image: &ImageView<Self>) -> Netpbm;
+}
+
+impl Codec for Gray<u8> {
+    fn from_netpbm(n: Netpbm) -> Result<Image<Self>, IoError> {
+        match &n.samples {
+            Samples::Int { maxval, values } if n.channels == 1 && *maxval <= 255 => {
+                let data = values.iter().map(|&v| Gray::new(v as u8)).collect();
+                Ok(Image::new(n.width, n.height, n.width, data))
+            }
+            _ => n.incompatible("Gray<u8>"),
+        }
+    }
+
+    fn to_netpbm(image: &ImageView<Self>) -> Netpbm {
+        let values = image.pixels().map(|p| p.value as u16).collect();
+        Netpbm {
+            width: image.width(),
+            height: image.height(),
+            channels: 1,
+            samples: Samples::Int { maxval: 255, values },
+        }
+    }
+}
+
+impl Codec for Gray<u16> {
+    fn from_netpbm(n: Netpbm) -> Result<Image<Self>, IoError> {
+        match &n.samples {
+            Samples::Int { values, .. } if n.channels == 1 => {
+                let data = values.iter().map(|&v| Gray::new(v)).collect();
+                Ok(Image::new(n.width, n.height, n.width, data))
+            }
+            _ => n.incompatible("Gray<u16>"),
+        }
+    }
+
+    fn to_netpbm(image: &ImageView<Self>) -> Netpbm {
+        let values = image.pixels().map(|p| p.value).collect();
+        Netpbm {
+            width: image.width(),
+            height: image.height(),
+            channels: 1,
+            samples: Samples::Int { maxval: u16::MAX, values },
+        }
+    }
+}
+
+impl Codec for Gray<f32> {
+    /// Integer samples are normalized by the file's maxval.
+    fn from_netpbm(n: Netpbm) -> Result<Image<Self>, IoError> {
+        let data = match &n.samples {
+            _ if n.channels != 1 => return n.incompatible("Gray<f32>"),
+            Samples::Float(values) => values.iter().map(|&v| Gray::new(v)).collect(),
+            Samples::Int { maxval, values } => {
+                values.iter().map(|&v| Gray::new(v as f32 / *maxval as f32)).collect()
+            }
+        };
+        Ok(Image::new(n.width, n.height, n.width, data))
+    }
+
+    fn to_netpbm(image: &ImageView<Self>) -> Netpbm {
+        Netpbm {
+            width: image.width(),
+            height: image.height(),
+            channels: 1,
+            samples: Samples::Float(image.pixels().map(|p| p.value).collect()),
+        }
+    }
+}
+
+impl Codec for Rgb<u8> {
+    fn from_netpbm(n: Netpbm) -> Result<Image<Self>, IoError> {
+        match &n.samples {
+            Samples::Int { maxval, values } if n.channels == 3 && *maxval <= 255 => {
+                let data =
+                    values.chunks_exact(3).map(|c| Rgb::new(c[0] as u8, c[1] as u8, c[2] as u8));
+                Ok(Image::new(n.width, n.height, n.width, data.collect()))
+            }
+            _ => n.incompatible("Rgb<u8>"),
+        }
+    }
+
+    fn to_netpbm(image: &ImageView<Self>) -> Netpbm {
+        let values = image.pixels().flat_map(|p| [p.r, p.g, p.b]).map(u16::from).collect();
+        Netpbm {
+            width: image.width(),
+            height: image.height(),
+            channels: 3,
+            samples: Samples::Int { maxval: 255, values },
+        }
+    }
+}
+
+fn read_byte(reader: &mut impl BufRead) -> Result<Option<u8>, IoError> {
+    let Some(&byte) = reader.fill_buf()?.first() else {
+        return Ok(None);
+    };
+    reader.consume(1);
+    Ok(Some(byte))
+}
+
+/// Read the next whitespace-delimited header token, skipping `#` comments.
+///
+/// Consumes exactly one whitespace byte after the token, so binary data starts right after the
+/// last header field.
+fn header_token(reader: &mut impl BufRead) -> Result<String, IoError> {
+    let mut token = String::new();
+    loop {
+        match read_byte(reader)? {
+            None if token.is_empty() => return Err(IoError::UnexpectedEof),
+            None => return Ok(token),
+            Some(b'#') if token.is_empty() => {
+                while !matches!(read_byte(reader)?, Some(b'\n') | None) {}
+            }
+            Some(b) if b.is_ascii_whitespace() => {
+                if !token.is_empty() {
+                    return Ok(token);
+                }
+            }
+            Some(b) => token.push(b as char),
+        }
+    }
+}
+
+fn header_value<T: FromStr>(reader: &mut impl BufRead, name: &str) -> Result<T, IoError> {
+    let token = header_token(reader)?;
+    token.parse().map_err(|_| IoError::InvalidHeader(format!("invalid {name} `{token}`")))
+}
+
+/// Read exactly `len` bytes of binary samples.
+///
+/// The buffer grows with the data actually read rather than with the size claimed by the
+/// header, so a truncated or malicious file fails with [`IoError::UnexpectedEof`] instead of a
+/// huge allocation.
+fn read_body(reader: &mut impl BufRead, len: usize) -> Result<Vec<u8>, IoError> {
+    let mut bytes = Vec::new();
+    reader.take(len as u64).read_to_end(&mut bytes)?;
+    if bytes.len() < len {
+        return Err(IoError::UnexpectedEof);
+    }
+    Ok(bytes)
+}
+
+pub fn decode(reader: &mut impl BufRead) -> Result<Netpbm, IoError> {
+    let mut magic = [0; 2];
+    reader.read_exact(&mut magic)?;
+    let (channels, ascii, float) = match &magic {
+        b"P2" => (1, true, false),
+        b"P3" => (3, true, false),
+        b"P5" => (1, false, false),
+        b"P6" => (3, false, false),
+        b"Pf" => (1, false, true),
+        b"PF" => (3, false, true),
+        _ => return Err(IoError::UnknownMagic(magic)),
+    };
+
+    let width: usize = header_value(reader, "width")?;
+    let height: usize = header_value(reader, "height")?;
+    if width == 0 || height == 0 {
+        return Err(ImageError::ZeroSize { width, height }.into());
+    }
+    // Byte counts of binary bodies are checked against overflow along with the sample count
+    let too_large = || IoError::InvalidHeader(format!("image size {width}x{height} too large"));
+    let len =
+        width.checked_mul(height).and_then(|n| n.checked_mul(channels)).ok_or_else(too_large)?;
+    let body_len = |sample_size: usize| len.checked_mul(sample_size).ok_or_else(too_large);
+
+    let samples = if float {
+        let scale: f32 = header_value(reader, "scale")?;
+        if scale == 0.0 || !scale.is_finite() {
+            return Err(IoError::InvalidHeader(format!("invalid scale `{scale}`")));
+        }
+
+        let bytes = read_body(reader, body_len(4)?)?;
+        let values: Vec<f32> = bytes
+            .chunks_exact(4)
+            .map(|c| {
+                let c = c.try_into().unwrap();
+                // A negative scale marks little-endian data
+                if scale < 0.0 { f32::from_le_bytes(c) } else { f32::from_be_bytes(c) }
+            })
+            .collect();
+
+        // PFM stores rows bottom to top
+        Samples::Float(values.chunks_exact(width * channels).rev().flatten().copied().collect())
+    } else {
+        let maxval: u16 = header_value(reader, "maxval")?;
+        if maxval == 0 {
+            return Err(IoError::InvalidHeader("maxval must be positive".to_string()));
+        }
+
+        let values: Vec<u16> = if ascii {
+            let mut body = String::new();
+            reader.read_to_string(&mut body)?;
+            let values = body
+                .split_whitespace()
+                .take(len)
+                .map(|t| t.parse().map_err(|_| IoError::InvalidSample(t.to_string())))
+                .collect::<Result<Vec<_>, _>>()?;
+            if values.len() < len {
+                return Err(IoError::UnexpectedEof);
+            }
+            values
+        } else if maxval <= 255 {
+            read_body(reader, body_len(1)?)?.into_iter().map(u16::from).collect()
+        } else {
+            let bytes = read_body(reader, body_len(2)?)?;
+            bytes.chunks_exact(2).map(|c| u16::from_be_bytes([c[0], c[1]])).collect()
+        };
+
+        if let Some(v) = values.iter().find(|&&v| v > maxval) {
+            return Err(IoError::InvalidSample(format!("{v} exceeds maxval {maxval}")));
+        }
+        Samples::Int { maxval, values }
+    };
+
+    Ok(Netpbm { width, height, channels, samples })
+}
+
+pub fn encode(netpbm: &Netpbm, writer: &mut impl Write, encoding: Encoding) -> Result<(), IoError> {
+    let (w, h) = (netpbm.width, netpbm.height);
+    match &netpbm.samples {
+        Samples::Int { maxval, values } => {
+            let magic = match (netpbm.channels, encoding) {
+                (1, Encoding::Binary) => "P5",
+                (1, Encoding::Ascii) => "P2",
+                (_, Encoding::Binary) => "P6",
+                (_, Encoding::Ascii) => "P3",
+            };
+            writeln!(writer, "{magic}\n{w} {h}\n{maxval}")?;
+
+            match encoding {
+                Encoding::Binary if *maxval <= 255 => {
+                    let bytes: Vec<u8> = values.iter().map(|&v| v as u8).collect();
+                    writer.write_all(&bytes)?;
+                }
+                Encoding::Binary => {
+                    let bytes: Vec<u8> = values.iter().flat_map(|v| v.to_be_bytes()).collect();
+                    writer.write_all(&bytes)?;
+                }
+                Encoding::Ascii => {
+                    for line in values.chunks(ASCII_SAMPLES_PER_LINE) {
+                        let line: Vec<String> = line.iter().map(u16::to_string).collect();
+                        writeln!(writer, "{}", line.join(" "))?;
+                    }
+                }
+            }
+        }
+        Samples::Float(values) => {
+            if encoding == Encoding::Ascii {
+                return Err(IoError::Unsupported("PFM has no ASCII encoding"));
+            }
+            let magic = if netpbm.channels == 1 { "Pf" } else { "PF" };
+            writeln!(writer, "{magic}\n{w} {h}\n-1.0")?;
+
+            let row = w * netpbm.channels;
+            let bytes: Vec<u8> =
+                values.chunks_exact(row).rev().flatten().flat_map(|v| v.to_le_bytes()).collect();
+            writer.write_all(&bytes)?;
+        }
+    }
+    Ok(())
+}
@@ -2,12 +2,39 @@
 //!
 //! Provides image types, filtering operations, pixel formats, and parallel processing utilities.
 
+pub mod bayer;
+pub mod border;
+pub mod error;
 pub mod filter;
+pub mod histogram;
 pub mod image;
+pub mod integral;
+#[cfg(feature = "image")]
+pub mod interop;
+pub mod io;
 pub mod parallel;
 pub mod pixel;
+pub mod pool;
+pub mod pyramid;
+pub mod threshold;
+pub mod yuv;
 
-pub use filter::{Kernel, apply_kernel, gaussian_3x3, gaussian_5x5, sobel};
-pub use image::ConvertTo;
+pub use bayer::{BayerPattern, Demosaic, bayer_to_gray, demosaic};
+pub use border::Border;
+pub use error::ImageError;
+pub use filter::{
+    Kernel, SeparableKernel, StructureTensor, apply_kernel, apply_kernel_border, apply_separable,
+    gaussian, gaussian_3x3, gaussian_3x3_border, gaussian_5x5, gaussian_5x5_border, sobel,
+    sobel_border,
+};
+pub use histogram::{Histogram, clahe, equalize, histogram_f32, histogram_u8};
+pub use image::{ConvertTo, FromPixel};
+pub use integral::IntegralImage;
 pub use parallel::{MaybeSend, MaybeSync};
-pub use pixel::{Gray, Rgb};
+pub use pixel::{Bgr, Bgra, Channel, Gray, Pixel, Rgb, Rgba};
+pub use pool::ImagePool;
+pub use pyramid::Pyramid;
+pub use threshold::{
+    AdaptiveMethod, adaptive_threshold, otsu_threshold, threshold, threshold_otsu,
+};
+pub use yuv::{I420, Nv12, YuvFrame, YuvRange, YuvStandard, Yuyv};
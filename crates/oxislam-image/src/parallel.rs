@@ -47,6 +47,32 @@ where
     }
 }
 
+/// Apply `f(y, row)` to the first `width` values of each of `height` rows spaced `stride` apart,
+/// parallelizing across rows when rayon is enabled.
+pub fn par_rows_mut<T: MaybeSend, F>(
+    data: &mut [T],
+    width: usize,
+    height: usize,
+    stride: usize,
+    f: F,
+) where
+    F: Fn(usize, &mut [T]) + MaybeSync,
+{
+    #[cfg(feature = "rayon")]
+    {
+        data.par_chunks_mut(stride)
+            .take(height)
+            .enumerate()
+            .for_each(|(y, row)| f(y, &mut row[..width]));
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        for (y, row) in data.chunks_mut(stride).take(height).enumerate() {
+            f(y, &mut row[..width]);
+        }
+    }
+}
+
 /// Flat-map over a range, parallelizing when rayon is enabled.
 pub fn par_flat_map<T: MaybeSend, F, I>(range: Range<usize>, f: F) -> Vec<T>
 where
@@ -1,3 +1,190 @@
+use crate::parallel::{MaybeSend, MaybeSync};
+
+/// A primitive channel value.
+///
+/// Integer channels span `0..=MAX_VALUE`; float channels use the normalized range `0.0..=1.0`.
+pub trait Channel: Copy + PartialOrd + MaybeSend + MaybeSync + 'static {
+    /// Full intensity: the type's maximum for integers, 1.0 for floats.
+    const MAX_VALUE: Self;
+
+    fn to_f32(self) -> f32;
+
+    /// Convert from `f32`, rounding and saturating for integer types.
+    fn from_f32(v: f32) -> Self;
+
+    fn to_f64(self) -> f64;
+
+    /// Convert from `f64`, rounding and saturating for integer types.
+    fn from_f64(v: f64) -> Self;
+
+    /// Saturating for integer types.
+    fn saturating_add(self, rhs: Self) -> Self;
+
+    /// Saturating for integer types.
+    fn saturating_sub(self, rhs: Self) -> Self;
+
+    /// Saturating for integer types.
+    fn saturating_mul(self, rhs: Self) -> Self;
+
+    /// Map to `0.0..=1.0` (for values within range).
+    #[inline]
+    fn to_normalized(self) -> f32 { self.to_f32() / Self::MAX_VALUE.to_f32() }
+
+    /// Map from `0.0..=1.0`, rounding and saturating for integer types.
+    #[inline]
+    fn from_normalized(v: f32) -> Self { Self::from_f32(v * Self::MAX_VALUE.to_f32()) }
+}
+
+impl Channel for u8 {
+    const MAX_VALUE: Self = u8::MAX;
+
+    #[inline]
+    fn to_f32(self) -> f32 { self as f32 }
+
+    #[inline]
+    fn from_f32(v: f32) -> Self { v.round() as u8 }
+
+    #[inline]
+    fn to_f64(self) -> f64 { self as f64 }
+
+    #[inline]
+    fn from_f64(v: f64) -> Self { v.round() as u8 }
+
+    #[inline]
+    fn saturating_add(self, rhs: Self) -> Self { u8::saturating_add(self, rhs) }
+
+    #[inline]
+    fn saturating_sub(self, rhs: Self) -> Self { u8::saturating_sub(self, rhs) }
+
+    #[inline]
+    fn saturating_mul(self, rhs: Self) -> Self { u8::saturating_mul(self, rhs) }
+}
+
+impl Channel for u16 {
+    const MAX_VALUE: Self = u16::MAX;
+
+    #[inline]
+    fn to_f32(self) -> f32 { self as f32 }
+
+    #[inline]
+    fn from_f32(v: f32) -> Self { v.round() as u16 }
+
+    #[inline]
+    fn to_f64(self) -> f64 { self as f64 }
+
+    #[inline]
+    fn from_f64(v: f64) -> Self { v.round() as u16 }
+
+    #[inline]
+    fn saturating_add(self, rhs: Self) -> Self { u16::saturating_add(self, rhs) }
+
+    #[inline]
+    fn saturating_sub(self, rhs: Self) -> Self { u16::saturating_sub(self, rhs) }
+
+    #[inline]
+    fn saturating_mul(self, rhs: Self) -> Self { u16::saturating_mul(self, rhs) }
+}
+
+impl Channel for f32 {
+    const MAX_VALUE: Self = 1.0;
+
+    #[inline]
+    fn to_f32(self) -> f32 { self }
+
+    #[inline]
+    fn from_f32(v: f32) -> Self { v }
+
+    #[inline]
+    fn to_f64(self) -> f64 { self as f64 }
+
+    #[inline]
+    fn from_f64(v: f64) -> Self { v as f32 }
+
+    #[inline]
+    fn saturating_add(self, rhs: Self) -> Self { self + rhs }
+
+    #[inline]
+    fn saturating_sub(self, rhs: Self) -> Self { self - rhs }
+
+    #[inline]
+    fn saturating_mul(self, rhs: Self) -> Self { self * rhs }
+}
+
+impl Channel for f64 {
+    const MAX_VALUE: Self = 1.0;
+
+    #[inline]
+    fn to_f32(self) -> f32 { self as f32 }
+
+    #[inline]
+    fn from_f32(v: f32) -> Self { v as f64 }
+
+    #[inline]
+    fn to_f64(self) -> f64 { self }
+
+    #[inline]
+    fn from_f64(v: f64) -> Self { v }
+
+    #[inline]
+    fn saturating_add(self, rhs: Self) -> Self { self + rhs }
+
+    #[inline]
+    fn saturating_sub(self, rhs: Self) -> Self { self - rhs }
+
+    #[inline]
+    fn saturating_mul(self, rhs: Self) -> Self { self * rhs }
+}
+
+/// A pixel made of `CHANNELS` values of a single [`Channel`] type.
+///
+/// Only [`Pixel::from_fn`] and [`Pixel::channel`] need to be implemented; the per-channel
+/// combinators are derived from them.
+pub trait Pixel: Copy + MaybeSend + MaybeSync + 'static {
+    type Channel: Channel;
+
+    /// The same pixel format with a different channel type.
+    type WithChannel<U: Channel>: Pixel<Channel = U>;
+
+    const CHANNELS: usize;
+
+    /// Build a pixel from a function of the channel index.
+    fn from_fn<F: FnMut(usize) -> Self::Channel>(f: F) -> Self;
+
+    /// Channel value at `index`, in memory order.
+    fn channel(&self, index: usize) -> Self::Channel;
+
+    #[inline]
+    fn map<F: FnMut(Self::Channel) -> Self::Channel>(self, mut f: F) -> Self {
+        Self::from_fn(|i| f(self.channel(i)))
+    }
+
+    #[inline]
+    fn map_channels<U: Channel, F: FnMut(Self::Channel) -> U>(
+        self,
+        mut f: F,
+    ) -> Self::WithChannel<U> {
+        Self::WithChannel::<U>::from_fn(|i| f(self.channel(i)))
+    }
+
+    #[inline]
+    fn zip_map<F: FnMut(Self::Channel, Self::Channel) -> Self::Channel>(
+        self,
+        other: Self,
+        mut f: F,
+    ) -> Self {
+        Self::from_fn(|i| f(self.channel(i), other.channel(i)))
+    }
+
+    /// Convert to another channel type, preserving the normalized intensity.
+    ///
+    /// Scales in `f64`, so converting to the same channel type is exact.
+    #[inline]
+    fn convert_channels<U: Channel>(self) -> Self::WithChannel<U> {
+        let scale = U::MAX_VALUE.to_f64() / Self::Channel::MAX_VALUE.to_f64();
+        self.map_channels(|c| U::from_f64(c.to_f64() * scale))
+    }
+}
+
 #[repr(transparent)]
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Gray<T> {
@@ -12,6 +199,32 @@ pub struct Rgb<T> {
     pub b: T,
 }
 
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rgba<T> {
+    pub r: T,
+    pub g: T,
+    pub b: T,
+    pub a: T,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bgr<T> {
+    pub b: T,
+    pub g: T,
+    pub r: T,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bgra<T> {
+    pub b: T,
+    pub g: T,
+    pub r: T,
+    pub a: T,
+}
+
 impl<T> Gray<T> {
     #[inline]
     pub const fn new(value: T) -> Self { Self { value } }
@@ -21,3 +234,109 @@ impl<T> Rgb<T> {
     #[inline]
     pub const fn new(r: T, g: T, b: T) -> Self { Self { r, g, b } }
 }
+
+impl<T> Rgba<T> {
+    #[inline]
+    pub const fn new(r: T, g: T, b: T, a: T) -> Self { Self { r, g, b, a } }
+}
+
+impl<T> Bgr<T> {
+    #[inline]
+    pub const fn new(b: T, g: T, r: T) -> Self { Self { b, g, r } }
+}
+
+impl<T> Bgra<T> {
+    #[inline]
+    pub const fn new(b: T, g: T, r: T, a: T) -> Self { Self { b, g, r, a } }
+}
+
+impl<T: Channel> Pixel for Gray<T> {
+    type Channel = T;
+    type WithChannel<U: Channel> = Gray<U>;
+    const CHANNELS: usize = 1;
+
+    #[inline]
+    fn from_fn<F: FnMut(usize) -> T>(mut f: F) -> Self { Gray::new(f(0)) }
+
+    #[inline]
+    fn channel(&self, index: usize) -> T {
+        debug_assert!(index < 1);
+        self.value
+    }
+}
+
+impl<T: Channel> Pixel for Rgb<T> {
+    type Channel = T;
+    type WithChannel<U: Channel> = Rgb<U>;
+    const CHANNELS: usize = 3;
+
+    #[inline]
+    fn from_fn<F: FnMut(usize) -> T>(mut f: F) -> Self { Rgb::new(f(0), f(1), f(2)) }
+
+    #[inline]
+    fn channel(&self, index: usize) -> T { [self.r, self.g, self.b][index] }
+}
+
+impl<T: Channel> Pixel for Bgr<T> {
+    type Channel = T;
+    type WithChannel<U: Channel> = Bgr<U>;
+    const CHANNELS: usize = 3;
+
+    #[inline]
+    fn from_fn<F: FnMut(usize) -> T>(mut f: F) -> Self { Bgr::new(f(0), f(1), f(2)) }
+
+    #[inline]
+    fn channel(&self, index: usize) -> T { [self.b, self.g, self.r][index] }
+}
+
+impl<T: Channel> Pixel for Rgba<T> {
+    type Channel = T;
+    type WithChannel<U: Channel> = Rgba<U>;
+    const CHANNELS: usize = 4;
+
+    #[inline]
+    fn from_fn<F: FnMut(usize) -> T>(mut f: F) -> Self { Rgba::new(f(0), f(1), f(2), f(3)) }
+
+    #[inline]
+    fn channel(&self, index: usize) -> T { [self.r, self.g, self.b, self.a][index] }
+}
+
+impl<T: Channel> Pixel for Bgra<T> {
+    type Channel = T;
+    type WithChannel<U: Channel> = Bgra<U>;
+    const CHANNELS: usize = 4;
+
+    #[inline]
+    fn from_fn<F: FnMut(usize) -> T>(mut f: F) -> Self { Bgra::new(f(0), f(1), f(2), f(3)) }
+
+    #[inline]
+    fn channel(&self, index: usize) -> T { [self.b, self.g, self.r, self.a][index] }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn channel_normalization() {
+        assert_eq!(255u8.to_normalized(), 1.0);
+        assert_eq!(u8::from_normalized(0.5), 128);
+        assert_eq!(u8::from_normalized(1.5), 255);
+        assert_eq!(u16::from_normalized(-0.1), 0);
+        assert_eq!(0.25f32.to_normalized(), 0.25);
+        assert_eq!(200u8.saturating_add(100), 255);
+    }
+
+    #[test]
+    fn per_channel_combinators() {
+        let px = Bgra::new(10u8, 20, 30, 40);
+        assert_eq!(Bgra::<u8>::CHANNELS, 4);
+        assert_eq!(px.channel(2), 30);
+        assert_eq!(px.map(|c| c / 10), Bgra::new(1, 2, 3, 4));
+        assert_eq!(px.zip_map(Bgra::new(1, 2, 3, 4), |a, b| a - b), Bgra::new(9, 18, 27, 36));
+
+        let f: Rgb<f32> = Rgb::new(0u8, 51, 255).convert_channels();
+        assert_eq!(f, Rgb::new(0.0, 0.2, 1.0));
+        assert_eq!(f.convert_channels::<u16>(), Rgb::new(0, 13107, 65535));
+    }
+}
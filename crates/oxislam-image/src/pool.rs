@@ -0,0 +1,88 @@
+//! Recycling of image buffers between calls.
+
+use crate::image::Image;
+
+/// A pool of image buffers that can be reused instead of reallocated.
+///
+/// Buffers returned with [`ImagePool::put`] are handed out again by [`ImagePool::take`] for any
+/// image that fits in their capacity, so a pipeline that takes and returns the same sizes on
+/// every call stops allocating after the first one.
+#[derive(Debug)]
+pub struct ImagePool<P> {
+    free: Vec<Vec<P>>,
+}
+
+impl<P> Default for ImagePool<P> {
+    fn default() -> Self { Self { free: Vec::new() } }
+}
+
+impl<P: Clone> ImagePool<P> {
+    pub fn new() -> Self { Self::default() }
+
+    /// A compact `width`x`height` image filled with `value`.
+    ///
+    /// Reuses the smallest free buffer large enough to hold the image, or allocates a new one if
+    /// none is.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` or `height` is zero.
+    pub fn take(&mut self, width: usize, height: usize, value: P) -> Image<P> {
+        let len = width * height;
+        let best = self
+            .free
+            .iter()
+            .enumerate()
+            .filter(|(_, buf)| buf.capacity() >= len)
+            .min_by_key(|(_, buf)| buf.capacity())
+            .map(|(i, _)| i);
+
+        let mut data = match best {
+            Some(i) => self.free.swap_remove(i),
+            None => Vec::with_capacity(len),
+        };
+        data.clear();
+        data.resize(len, value);
+        Image::new(width, height, width, data)
+    }
+
+    /// Return an image's buffer to the pool.
+    pub fn put(&mut self, image: Image<P>) { self.free.push(image.into_data()); }
+
+    /// Number of free buffers.
+    #[inline]
+    pub fn len(&self) -> usize { self.free.len() }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool { self.free.is_empty() }
+
+    /// Drop all free buffers.
+    pub fn clear(&mut self) { self.free.clear(); }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pixel::Gray;
+
+    #[test]
+    fn reuses_best_fitting_buffer() {
+        let mut pool = ImagePool::new();
+        let small = pool.take(4, 4, Gray::new(0.0f32));
+        let large = pool.take(16, 16, Gray::new(0.0f32));
+        let (small_ptr, large_ptr) = (small.data().as_ptr(), large.data().as_ptr());
+        pool.put(large);
+        pool.put(small);
+        assert_eq!(pool.len(), 2);
+
+        // The small buffer fits exactly, the large one is the only fit for a bigger image
+        let img = pool.take(2, 8, Gray::new(1.0));
+        assert_eq!(img.data().as_ptr(), small_ptr);
+        assert!(img.view().pixels().all(|p| p.value == 1.0));
+
+        let img = pool.take(10, 10, Gray::new(0.5));
+        assert_eq!(img.data().as_ptr(), large_ptr);
+        assert_eq!((img.width(), img.height(), img.stride()), (10, 10, 10));
+        assert!(pool.is_empty());
+    }
+}
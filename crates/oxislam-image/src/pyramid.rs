@@ -0,0 +1,144 @@
+//! Multi-scale image pyramids.
+
+use crate::border::Border;
+use crate::filter::gaussian;
+use crate::image::{Image, ImageView, Interpolation, resize};
+use crate::parallel::par_row_collect;
+use crate::pixel::Gray;
+
+/// A stack of progressively downscaled images.
+///
+/// Level 0 is a copy of the input. Each following level is smoothed to suppress aliasing and
+/// resampled to `1 / scale_factor` of the previous level's size, e.g. a factor of 2.0 gives a
+/// classic Gaussian pyramid and 1.2 an ORB-style pyramid.
+///
+/// Coordinates follow the pixel-center convention: pixel `(x, y)` covers the square from
+/// `(x - 0.5, y - 0.5)` to `(x + 0.5, y + 0.5)`.
+#[derive(Debug)]
+pub struct Pyramid {
+    scale_factor: f32,
+    levels: Vec<Image<Gray<f32>>>,
+    // Per-axis scale of each level relative to level 0, from the actual rounded level sizes.
+    scales: Vec<(f32, f32)>,
+}
+
+impl Pyramid {
+    /// Build a pyramid with up to `num_levels` levels.
+    ///
+    /// Construction stops early once a level would no longer shrink, so
+    /// [`Pyramid::num_levels`] may be less than requested.
+    pub fn new(image: &ImageView<Gray<f32>>, num_levels: usize, scale_factor: f32) -> Self {
+        assert!(num_levels > 0, "Pyramid must have at least one level");
+        assert!(scale_factor > 1.0, "Scale factor must be greater than 1");
+
+        let (w0, h0) = (image.width(), image.height());
+        let base = Image::new(w0, h0, w0, par_row_collect(w0, h0, |x, y| *image.get(x, y)));
+
+        let mut levels = vec![base];
+        let mut scales = vec![(1.0, 1.0)];
+
+        // Standard deviation that, combined with the already present blur, approximates the
+        // blur expected at the next level.
+        let sigma = 0.5 * (scale_factor * scale_factor - 1.0).sqrt();
+
+        while levels.len() < num_levels {
+            let prev = levels.last().unwrap();
+            let w = (prev.width() as f32 / scale_factor).round() as usize;
+            let h = (prev.height() as f32 / scale_factor).round() as usize;
+            if w == 0 || h == 0 || (w, h) == (prev.width(), prev.height()) {
+                break;
+            }
+
+            let smoothed = gaussian(&prev.view(), sigma, Border::Reflect101);
+            let next = resize(&smoothed.view(), w, h, Interpolation::Bilinear);
+
+            scales.push((w0 as f32 / w as f32, h0 as f32 / h as f32));
+            levels.push(next);
+        }
+
+        Self { scale_factor, levels, scales }
+    }
+
+    #[inline]
+    pub fn num_levels(&self) -> usize { self.levels.len() }
+
+    #[inline]
+    pub fn scale_factor(&self) -> f32 { self.scale_factor }
+
+    #[inline]
+    pub fn level(&self, level: usize) -> Option<ImageView<'_, Gray<f32>>> {
+        self.levels.get(level).map(Image::view)
+    }
+
+    pub fn levels(&self) -> impl Iterator<Item = ImageView<'_, Gray<f32>>> {
+        self.levels.iter().map(Image::view)
+    }
+
+    /// Per-axis scale of `level` relative to level 0.
+    #[inline]
+    pub fn scale(&self, level: usize) -> (f32, f32) { self.scales[level] }
+
+    /// Map a position in level `from` to the corresponding position in level `to`.
+    pub fn map_coord(&self, x: f32, y: f32, from: usize, to: usize) -> (f32, f32) {
+        let (fsx, fsy) = self.scales[from];
+        let (tsx, tsy) = self.scales[to];
+        ((x + 0.5) * fsx / tsx - 0.5, (y + 0.5) * fsy / tsy - 0.5)
+    }
+
+    /// Map a position in `level` to level 0.
+    #[inline]
+    pub fn to_base(&self, x: f32, y: f32, level: usize) -> (f32, f32) {
+        self.map_coord(x, y, level, 0)
+    }
+
+    /// Map a position in level 0 to `level`.
+    #[inline]
+    pub fn from_base(&self, x: f32, y: f32, level: usize) -> (f32, f32) {
+        self.map_coord(x, y, 0, level)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn level_sizes() {
+        let img = Image::filled(64, 48, Gray::new(0.5f32));
+
+        let pyramid = Pyramid::new(&img.view(), 4, 2.0);
+        let sizes: Vec<_> = pyramid.levels().map(|l| (l.width(), l.height())).collect();
+        assert_eq!(sizes, [(64, 48), (32, 24), (16, 12), (8, 6)]);
+
+        let pyramid = Pyramid::new(&img.view(), 3, 1.2);
+        let sizes: Vec<_> = pyramid.levels().map(|l| (l.width(), l.height())).collect();
+        assert_eq!(sizes, [(64, 48), (53, 40), (44, 33)]);
+    }
+
+    #[test]
+    fn stops_when_levels_stop_shrinking() {
+        let img = Image::filled(4, 4, Gray::new(1.0f32));
+        let pyramid = Pyramid::new(&img.view(), 10, 2.0);
+
+        assert_eq!(pyramid.num_levels(), 3);
+        assert!(pyramid.level(3).is_none());
+        for level in pyramid.levels() {
+            assert!(level.pixels().all(|p| (p.value - 1.0).abs() < 1e-5));
+        }
+    }
+
+    #[test]
+    fn coordinate_mapping() {
+        let img = Image::filled(64, 48, Gray::new(0.0f32));
+        let pyramid = Pyramid::new(&img.view(), 3, 2.0);
+
+        // Pixel-center convention: (0.5, 0.5) at level 1 covers (1.5, 1.5) at level 0
+        assert_eq!(pyramid.to_base(0.5, 0.5, 1), (1.5, 1.5));
+        assert_eq!(pyramid.from_base(1.5, 1.5, 1), (0.5, 0.5));
+        assert_eq!(pyramid.map_coord(10.0, 6.0, 2, 1), (20.5, 12.5));
+
+        let (x, y) = pyramid.from_base(17.3, 9.8, 2);
+        let (bx, by) = pyramid.to_base(x, y, 2);
+        assert!((bx - 17.3).abs() < 1e-5 && (by - 9.8).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,138 @@
+//! Binarization of grayscale images.
+//!
+//! All functions return masks with 255 on foreground pixels, those brighter than the threshold,
+//! and 0 elsewhere.
+
+use crate::border::Border;
+use crate::filter::{box_filter, gaussian};
+use crate::histogram::histogram_u8;
+use crate::image::{Image, ImageView, map, map2};
+use crate::pixel::{Channel, Gray};
+
+const FOREGROUND: u8 = u8::MAX;
+
+/// How [`adaptive_threshold`] estimates the local brightness around each pixel.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AdaptiveMethod {
+    /// Mean over the `(2 * radius + 1)`-sized square window, clipped at the image border.
+    Mean { radius: usize },
+    /// Gaussian-weighted mean with standard deviation `sigma`.
+    Gaussian { sigma: f32 },
+}
+
+/// Mask of pixels brighter than `level`.
+pub fn threshold<T: Channel>(image: &ImageView<Gray<T>>, level: T) -> Image<Gray<u8>> {
+    map(image, |p| Gray::new(if p.value > level { FOREGROUND } else { 0 }))
+}
+
+/// Otsu's threshold: the value splitting the histogram into two classes with maximal
+/// between-class variance.
+///
+/// Pixels up to and including the returned value form the background class, so it can be
+/// passed straight to [`threshold`]. Returns 0 for images with a single distinct value.
+pub fn otsu_threshold(image: &ImageView<Gray<u8>>) -> u8 {
+    let hist = histogram_u8(image);
+    let hist = hist.counts();
+
+    let total: u64 = hist.iter().sum();
+    let total_sum: f64 = hist.iter().enumerate().map(|(v, &n)| v as f64 * n as f64).sum();
+    let (mut below, mut below_sum) = (0u64, 0.0f64);
+    let (mut best, mut best_variance) = (0u8, 0.0f64);
+
+    for (v, &n) in hist.iter().enumerate() {
+        below += n;
+        below_sum += v as f64 * n as f64;
+        let above = total - below;
+        if below == 0 {
+            continue;
+        }
+        if above == 0 {
+            break;
+        }
+
+        let mean_below = below_sum / below as f64;
+        let mean_above = (total_sum - below_sum) / above as f64;
+        let variance = below as f64 * above as f64 * (mean_below - mean_above).powi(2);
+        if variance > best_variance {
+            (best, best_variance) = (v as u8, variance);
+        }
+    }
+    best
+}
+
+/// Binarize with [`otsu_threshold`], returning the threshold along with the mask.
+pub fn threshold_otsu(image: &ImageView<Gray<u8>>) -> (u8, Image<Gray<u8>>) {
+    let t = otsu_threshold(image);
+    (t, threshold(image, t))
+}
+
+/// Mask of pixels brighter than their local mean minus `offset`, in channel units.
+///
+/// Comparing against the neighborhood instead of a global value copes with uneven lighting. A
+/// positive `offset` keeps flat regions, where pixels hover around their mean, in the
+/// foreground; a negative one pushes them to the background.
+pub fn adaptive_threshold<T: Channel>(
+    image: &ImageView<Gray<T>>,
+    method: AdaptiveMethod,
+    offset: f32,
+) -> Image<Gray<u8>> {
+    let local = match method {
+        AdaptiveMethod::Mean { radius } => box_filter(image, radius),
+        AdaptiveMethod::Gaussian { sigma } => {
+            let image = map(image, |p| Gray::new(p.value.to_f32()));
+            gaussian(&image.view(), sigma, Border::Replicate)
+        }
+    };
+    map2(image, &local.view(), |p, m| {
+        Gray::new(if p.value.to_f32() > m.value - offset { FOREGROUND } else { 0 })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn otsu_splits_bimodal_histogram() {
+        let data =
+            (0..100).map(|i| Gray::new(if i < 60 { 40 + i % 7 } else { 180 + i % 5 })).collect();
+        let img = Image::new(10, 10, 10, data);
+
+        let (t, mask) = threshold_otsu(&img.view());
+        assert!((46..180).contains(&t), "threshold {t}");
+        assert_eq!(mask.view().pixels().filter(|p| p.value == 255).count(), 40);
+
+        let flat = Image::filled(4, 4, Gray::new(90u8));
+        assert_eq!(otsu_threshold(&flat.view()), 0);
+    }
+
+    #[test]
+    fn adaptive_threshold_handles_uneven_lighting() {
+        // Brightness ramps from 20 to 230 across the image, with dark dots every 8 pixels
+        let (w, h) = (48, 16);
+        let dot = |x: usize, y: usize| x % 8 == 4 && y % 8 == 4;
+        let data = (0..w * h)
+            .map(|i| {
+                let (x, y) = (i % w, i / w);
+                let base = 20.0 + 210.0 * x as f32 / (w - 1) as f32;
+                Gray::new(if dot(x, y) { base * 0.5 } else { base })
+            })
+            .collect();
+        let img = Image::new(w, h, w, data);
+
+        // No global threshold separates dots on the bright side from background on the dark side
+        let global = threshold(&img.view(), 100.0);
+        assert_eq!(global.get(1, 1).value, 0);
+
+        for method in [AdaptiveMethod::Mean { radius: 3 }, AdaptiveMethod::Gaussian { sigma: 2.0 }]
+        {
+            let mask = adaptive_threshold(&img.view(), method, 10.0);
+            for y in 0..h {
+                for x in 0..w {
+                    let expected = if dot(x, y) { 0 } else { 255 };
+                    assert_eq!(mask.get(x, y).value, expected, "{method:?} at ({x}, {y})");
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,363 @@
+//! YUV camera frame formats and conversion to gray and RGB images.
+
+use crate::error::ImageError;
+use crate::image::{Image, ImageView};
+use crate::parallel::{MaybeSync, par_row_collect};
+use crate::pixel::{Gray, Rgb};
+
+/// Color matrix used to encode the chroma channels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum YuvStandard {
+    /// ITU-R BT.601, used by most SD and webcam sources.
+    Bt601,
+    /// ITU-R BT.709, used by HD sources.
+    Bt709,
+}
+
+/// Value range of the encoded samples.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum YuvRange {
+    /// Luma and chroma span `0..=255`.
+    Full,
+    /// Luma spans `16..=235` and chroma `16..=240`.
+    Limited,
+}
+
+impl YuvStandard {
+    /// Luma weights `(Kr, Kb)` of the red and blue channels.
+    #[inline]
+    fn weights(self) -> (f32, f32) {
+        match self {
+            YuvStandard::Bt601 => (0.299, 0.114),
+            YuvStandard::Bt709 => (0.2126, 0.0722),
+        }
+    }
+}
+
+impl YuvRange {
+    /// Normalized luma in `0.0..=1.0`.
+    #[inline]
+    fn luma(self, y: u8) -> f32 {
+        match self {
+            YuvRange::Full => y as f32 / 255.0,
+            YuvRange::Limited => ((y as f32 - 16.0) / 219.0).clamp(0.0, 1.0),
+        }
+    }
+
+    /// Normalized chroma in `-0.5..=0.5`.
+    #[inline]
+    fn chroma(self, c: u8) -> f32 {
+        match self {
+            YuvRange::Full => (c as f32 - 128.0) / 255.0,
+            YuvRange::Limited => ((c as f32 - 128.0) / 224.0).clamp(-0.5, 0.5),
+        }
+    }
+}
+
+#[inline]
+fn yuv_to_rgb(yuv: [u8; 3], standard: YuvStandard, range: YuvRange) -> Rgb<u8> {
+    let (kr, kb) = standard.weights();
+    let kg = 1.0 - kr - kb;
+    let y = range.luma(yuv[0]);
+    let u = range.chroma(yuv[1]);
+    let v = range.chroma(yuv[2]);
+
+    let r = y + 2.0 * (1.0 - kr) * v;
+    let g = y - 2.0 * kb * (1.0 - kb) / kg * u - 2.0 * kr * (1.0 - kr) / kg * v;
+    let b = y + 2.0 * (1.0 - kb) * u;
+
+    let to_u8 = |c: f32| (c * 255.0).round().clamp(0.0, 255.0) as u8;
+    Rgb::new(to_u8(r), to_u8(g), to_u8(b))
+}
+
+/// A frame with one luma and two (possibly subsampled) chroma samples per pixel.
+pub trait YuvFrame: MaybeSync {
+    fn width(&self) -> usize;
+
+    fn height(&self) -> usize;
+
+    /// Raw `[y, u, v]` samples at pixel `(x, y)`.
+    fn yuv(&self, x: usize, y: usize) -> [u8; 3];
+
+    /// Normalized luma, expanding limited-range samples to `0.0..=1.0`.
+    fn to_gray_f32(&self, range: YuvRange) -> Image<Gray<f32>> {
+        let (w, h) = (self.width(), self.height());
+        Image::new(w, h, w, par_row_collect(w, h, |x, y| Gray::new(range.luma(self.yuv(x, y)[0]))))
+    }
+
+    fn to_rgb(&self, standard: YuvStandard, range: YuvRange) -> Image<Rgb<u8>> {
+        let (w, h) = (self.width(), self.height());
+        let data = par_row_collect(w, h, |x, y| yuv_to_rgb(self.yuv(x, y), standard, range));
+        Image::new(w, h, w, data)
+    }
+}
+
+/// Semi-planar 4:2:0 frame: a full-resolution Y plane followed by an interleaved UV plane at
+/// half resolution in both dimensions.
+#[derive(Debug)]
+pub struct Nv12<'a> {
+    y: ImageView<'a, Gray<u8>>,
+    uv: ImageView<'a, Gray<u8>>,
+}
+
+/// `a * b` as a byte count for a `width`x`height` frame, failing if it overflows.
+fn checked_len(a: usize, b: usize, width: usize, height: usize) -> Result<usize, ImageError> {
+    a.checked_mul(b).ok_or(ImageError::TooLarge { width, height })
+}
+
+impl<'a> Nv12<'a> {
+    /// Wrap separate planes. `uv` holds `2 * ceil(width / 2)` interleaved samples per row and
+    /// `ceil(height / 2)` rows.
+    pub fn new(
+        y: ImageView<'a, Gray<u8>>,
+        uv: ImageView<'a, Gray<u8>>,
+    ) -> Result<Self, ImageError> {
+        let expected = (y.width().div_ceil(2) * 2, y.height().div_ceil(2));
+        if (uv.width(), uv.height()) != expected {
+            return Err(ImageError::SizeMismatch { expected, actual: (uv.width(), uv.height()) });
+        }
+        Ok(Self { y, uv })
+    }
+
+    /// Wrap a contiguous buffer where both planes use the row stride `stride` (in bytes).
+    ///
+    /// UV rows hold `2 * ceil(width / 2)` bytes, one more than an odd `width`. With a tightly
+    /// packed Y plane (`stride == width`), the UV plane is taken to be tightly packed as well.
+    pub fn from_bytes(
+        data: &'a [u8],
+        width: usize,
+        height: usize,
+        stride: usize,
+    ) -> Result<Self, ImageError> {
+        let y_len = checked_len(stride, height, width, height)?.min(data.len());
+        let y = ImageView::from_bytes(&data[..y_len], width, height, stride)?;
+        let uv_width = checked_len(width.div_ceil(2), 2, width, height)?;
+        let uv_stride = stride.max(uv_width);
+        let uv = ImageView::from_bytes(&data[y_len..], uv_width, height.div_ceil(2), uv_stride)?;
+        Self::new(y, uv)
+    }
+
+    /// The luma plane, without copying.
+    #[inline]
+    pub fn luma(&self) -> ImageView<'a, Gray<u8>> {
+        ImageView::new(self.y.data(), self.y.width(), self.y.height(), self.y.stride())
+    }
+}
+
+impl YuvFrame for Nv12<'_> {
+    #[inline]
+    fn width(&self) -> usize { self.y.width() }
+
+    #[inline]
+    fn height(&self) -> usize { self.y.height() }
+
+    #[inline]
+    fn yuv(&self, x: usize, y: usize) -> [u8; 3] {
+        let (cx, cy) = ((x / 2) * 2, y / 2);
+        [self.y.get(x, y).value, self.uv.get(cx, cy).value, self.uv.get(cx + 1, cy).value]
+    }
+}
+
+/// Planar 4:2:0 frame: a full-resolution Y plane followed by U and V planes at half resolution
+/// in both dimensions.
+#[derive(Debug)]
+pub struct I420<'a> {
+    y: ImageView<'a, Gray<u8>>,
+    u: ImageView<'a, Gray<u8>>,
+    v: ImageView<'a, Gray<u8>>,
+}
+
+impl<'a> I420<'a> {
+    /// Wrap separate planes. `u` and `v` must be `ceil(width / 2)`x`ceil(height / 2)`.
+    pub fn new(
+        y: ImageView<'a, Gray<u8>>,
+        u: ImageView<'a, Gray<u8>>,
+        v: ImageView<'a, Gray<u8>>,
+    ) -> Result<Self, ImageError> {
+        let expected = (y.width().div_ceil(2), y.height().div_ceil(2));
+        for plane in [&u, &v] {
+            if (plane.width(), plane.height()) != expected {
+                return Err(ImageError::SizeMismatch {
+                    expected,
+                    actual: (plane.width(), plane.height()),
+                });
+            }
+        }
+        Ok(Self { y, u, v })
+    }
+
+    /// Wrap a tightly packed buffer holding the Y, U and V planes back to back.
+    pub fn from_bytes(data: &'a [u8], width: usize, height: usize) -> Result<Self, ImageError> {
+        let (cw, ch) = (width.div_ceil(2), height.div_ceil(2));
+        let y_len = checked_len(width, height, width, height)?.min(data.len());
+        let u_len = checked_len(cw, ch, width, height)?.min(data.len() - y_len);
+
+        let y = ImageView::from_bytes(&data[..y_len], width, height, width)?;
+        let u = ImageView::from_bytes(&data[y_len..y_len + u_len], cw, ch, cw)?;
+        let v = ImageView::from_bytes(&data[y_len + u_len..], cw, ch, cw)?;
+        Self::new(y, u, v)
+    }
+
+    /// The luma plane, without copying.
+    #[inline]
+    pub fn luma(&self) -> ImageView<'a, Gray<u8>> {
+        ImageView::new(self.y.data(), self.y.width(), self.y.height(), self.y.stride())
+    }
+}
+
+impl YuvFrame for I420<'_> {
+    #[inline]
+    fn width(&self) -> usize { self.y.width() }
+
+    #[inline]
+    fn height(&self) -> usize { self.y.height() }
+
+    #[inline]
+    fn yuv(&self, x: usize, y: usize) -> [u8; 3] {
+        let (cx, cy) = (x / 2, y / 2);
+        [self.y.get(x, y).value, self.u.get(cx, cy).value, self.v.get(cx, cy).value]
+    }
+}
+
+/// Packed 4:2:2 frame with samples ordered `Y0 U Y1 V` for each pair of pixels.
+#[derive(Debug)]
+pub struct Yuyv<'a> {
+    data: ImageView<'a, Gray<u8>>,
+    width: usize,
+}
+
+impl<'a> Yuyv<'a> {
+    /// Wrap a packed buffer with a row stride of `stride` bytes.
+    pub fn from_bytes(
+        data: &'a [u8],
+        width: usize,
+        height: usize,
+        stride: usize,
+    ) -> Result<Self, ImageError> {
+        let row_len = checked_len(width.div_ceil(2), 4, width, height)?;
+        let data = ImageView::from_bytes(data, row_len, height, stride)?;
+        Ok(Self { data, width })
+    }
+
+    /// Copy out the luma samples.
+    pub fn luma(&self) -> Image<Gray<u8>> {
+        let (w, h) = (self.width, self.data.height());
+        Image::new(w, h, w, par_row_collect(w, h, |x, y| *self.data.get(2 * x, y)))
+    }
+}
+
+impl YuvFrame for Yuyv<'_> {
+    #[inline]
+    fn width(&self) -> usize { self.width }
+
+    #[inline]
+    fn height(&self) -> usize { self.data.height() }
+
+    #[inline]
+    fn yuv(&self, x: usize, y: usize) -> [u8; 3] {
+        let base = (x / 2) * 4;
+        [
+            self.data.get(2 * x, y).value,
+            self.data.get(base + 1, y).value,
+            self.data.get(base + 3, y).value,
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(px: Rgb<u8>, expected: (u8, u8, u8)) {
+        let d = |a: u8, b: u8| (a as i32 - b as i32).abs();
+        assert!(
+            d(px.r, expected.0) <= 1 && d(px.g, expected.1) <= 1 && d(px.b, expected.2) <= 1,
+            "expected {expected:?}, got {px:?}"
+        );
+    }
+
+    #[test]
+    fn primaries_roundtrip() {
+        // Full-range BT.601 encoding of pure red, green and blue
+        assert_close(yuv_to_rgb([76, 85, 255], YuvStandard::Bt601, YuvRange::Full), (255, 0, 0));
+        assert_close(yuv_to_rgb([150, 44, 21], YuvStandard::Bt601, YuvRange::Full), (0, 255, 0));
+        assert_close(yuv_to_rgb([29, 255, 107], YuvStandard::Bt601, YuvRange::Full), (0, 0, 255));
+        // Limited-range BT.709 encoding of pure red
+        assert_close(
+            yuv_to_rgb([63, 102, 240], YuvStandard::Bt709, YuvRange::Limited),
+            (255, 0, 0),
+        );
+    }
+
+    #[test]
+    fn limited_range_extremes() {
+        let black = yuv_to_rgb([16, 128, 128], YuvStandard::Bt709, YuvRange::Limited);
+        let white = yuv_to_rgb([235, 128, 128], YuvStandard::Bt709, YuvRange::Limited);
+        assert_eq!(black, Rgb::new(0, 0, 0));
+        assert_eq!(white, Rgb::new(255, 255, 255));
+    }
+
+    #[test]
+    fn nv12_layout() {
+        // 4x2 frame: Y plane, then one row of interleaved UV for two 2x2 blocks
+        #[rustfmt::skip]
+        let data: [u8; 12] = [
+            10, 20, 30, 40,
+            50, 60, 70, 80,
+            1, 2, 3, 4,
+        ];
+        let frame = Nv12::from_bytes(&data, 4, 2, 4).unwrap();
+
+        assert_eq!(frame.luma().get(3, 1).value, 80);
+        assert_eq!(frame.yuv(1, 1), [60, 1, 2]);
+        assert_eq!(frame.yuv(2, 0), [30, 3, 4]);
+        assert!(Nv12::from_bytes(&data[..11], 4, 2, 4).is_err());
+        // Dimensions whose plane sizes overflow are rejected rather than wrapped
+        let overflow = Nv12::from_bytes(&data, 4, usize::MAX / 2, usize::MAX / 4);
+        assert!(matches!(overflow, Err(ImageError::TooLarge { .. })));
+
+        // Odd width with a tight stride: UV rows are one byte wider than Y rows
+        #[rustfmt::skip]
+        let odd: [u8; 10] = [
+            10, 20, 30,
+            40, 50, 60,
+            1, 2, 3, 4,
+        ];
+        let frame = Nv12::from_bytes(&odd, 3, 2, 3).unwrap();
+        assert_eq!(frame.yuv(1, 1), [50, 1, 2]);
+        assert_eq!(frame.yuv(2, 0), [30, 3, 4]);
+    }
+
+    #[test]
+    fn i420_layout() {
+        #[rustfmt::skip]
+        let data: [u8; 12] = [
+            10, 20, 30, 40,
+            50, 60, 70, 80,
+            1, 2,
+            3, 4,
+        ];
+        let frame = I420::from_bytes(&data, 4, 2).unwrap();
+
+        assert_eq!(frame.yuv(1, 1), [60, 1, 3]);
+        assert_eq!(frame.yuv(3, 0), [40, 2, 4]);
+
+        let gray = frame.to_gray_f32(YuvRange::Full);
+        assert_eq!(gray.get(0, 0).value, 10.0 / 255.0);
+
+        let overflow = I420::from_bytes(&data, usize::MAX / 2, 4);
+        assert!(matches!(overflow, Err(ImageError::TooLarge { .. })));
+    }
+
+    #[test]
+    fn yuyv_layout() {
+        // Two gray pixel pairs with neutral chroma
+        let data: [u8; 8] = [16, 128, 235, 128, 126, 128, 126, 128];
+        let frame = Yuyv::from_bytes(&data, 4, 1, 8).unwrap();
+
+        assert_eq!(frame.luma().as_raw(), &[16, 235, 126, 126]);
+        let rgb = frame.to_rgb(YuvStandard::Bt601, YuvRange::Limited);
+        assert_eq!(*rgb.get(0, 0), Rgb::new(0, 0, 0));
+        assert_eq!(*rgb.get(1, 0), Rgb::new(255, 255, 255));
+    }
+}